@@ -6,6 +6,12 @@ pub const GRAY: Color = Color {
     b: 0.8,
     a: 1.0,
 };
+pub const DARK_GRAY: Color = Color {
+    r: 0.3,
+    g: 0.3,
+    b: 0.3,
+    a: 1.0,
+};
 pub const BLACK: Color = Color {
     r: 0.0,
     g: 0.0,
@@ -4,6 +4,8 @@ use std::path;
 
 use failure::{self, Fail};
 use ggez::{self, audio, graphics};
+use ron;
+use serde::de::DeserializeOwned;
 use warmy;
 
 use common::error::*;
@@ -64,6 +66,33 @@ impl warmy::Load<ggez::Context> for Image {
     }
 }
 
+/// Game data read from a RON file, so it can be tuned while the game runs.
+#[derive(Debug, Clone)]
+pub struct Ron<T>(pub T);
+impl<T> warmy::Load<ggez::Context> for Ron<T>
+where
+    T: DeserializeOwned + 'static,
+{
+    type Key = warmy::FSKey;
+    type Error = failure::Compat<failure::Error>;
+    fn load(
+        key: Self::Key,
+        store: &mut warmy::Storage<ggez::Context>,
+        ctx: &mut ggez::Context,
+    ) -> Result<warmy::Loaded<Self>, Self::Error> {
+        let path = warmy_to_ggez_path(key.as_path(), store.root());
+        debug!("Loading data {:?} from file {:?}", path, key.as_path());
+
+        let file = ctx
+            .filesystem
+            .open(&path)
+            .map_err(|e| failure::Error::from(GgezError::from(e)).compat())?;
+        ron::de::from_reader(file)
+            .map(|x| warmy::Loaded::from(Ron(x)))
+            .map_err(|e| failure::Error::from(e).compat())
+    }
+}
+
 /// A wrapper for a ggez SoundData, so we can implement warmy's `Load` trait on it.
 #[derive(Debug, Clone)]
 pub struct SoundData(pub audio::SoundData);
@@ -15,3 +15,9 @@ pub fn center_rect_vertically(rect: Rect, height: f32) -> Point2 {
     let vpadding = (height - rect.h) / 2.0;
     Point2::new(rect.x, rect.y + vpadding)
 }
+
+/// Formats a number of seconds as mm:ss. Minutes keep counting past an hour
+/// rather than wrapping.
+pub fn format_clock(seconds: u64) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
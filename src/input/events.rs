@@ -5,10 +5,34 @@ use std::hash::Hash;
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
 enum InputEvent {
     Key(Keycode),
+    KeyChord(KeyModifier, Keycode),
     MouseButton(MouseButton),
     MouseMotion,
 }
 
+/// Logical modifier keys, so that a binding doesn't have to care whether the
+/// left or right variant of a modifier was held.
+#[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
+pub enum KeyModifier {
+    Ctrl,
+    Alt,
+    Shift,
+}
+
+impl KeyModifier {
+    fn from_mod(keymod: Mod) -> Option<Self> {
+        if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LGUIMOD | Mod::RGUIMOD) {
+            Some(KeyModifier::Ctrl)
+        } else if keymod.intersects(Mod::LALTMOD | Mod::RALTMOD) {
+            Some(KeyModifier::Alt)
+        } else if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+            Some(KeyModifier::Shift)
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum InputEffect<Axes, Buttons>
 where
@@ -64,6 +88,9 @@ where
     // instead of BTreeMap. ♥?
     // Binding of keys to input values.
     bindings: HashMap<InputEvent, InputEffect<Axes, Buttons>>,
+    // What each key held down was resolved to when it was pressed, so that
+    // letting go of a modifier first doesn't change what the release means.
+    held: HashMap<Keycode, InputEffect<Axes, Buttons>>,
 }
 
 impl<Axes, Buttons> InputBinding<Axes, Buttons>
@@ -74,6 +101,7 @@ where
     pub fn new() -> Self {
         InputBinding {
             bindings: HashMap::new(),
+            held: HashMap::new(),
         }
     }
 
@@ -93,6 +121,21 @@ where
         self
     }
 
+    /// Adds a key binding connecting the given keycode, pressed while
+    /// holding the given modifier, to the given logical button.
+    pub fn bind_key_chord_to_button(
+        mut self,
+        modifier: KeyModifier,
+        keycode: Keycode,
+        button: Buttons,
+    ) -> Self {
+        self.bindings.insert(
+            InputEvent::KeyChord(modifier, keycode),
+            InputEffect::Button(button, None),
+        );
+        self
+    }
+
     pub fn bind_mouse_to_button(mut self, mouse: MouseButton, button: Buttons) -> Self {
         self.bindings.insert(
             InputEvent::MouseButton(mouse),
@@ -110,8 +153,34 @@ where
     }
 
     /// Takes an physical input type and turns it into a logical input type (keycode -> axis/button).
-    pub fn resolve_key(&self, keycode: Keycode) -> Option<InputEffect<Axes, Buttons>> {
-        self.bindings.get(&InputEvent::Key(keycode)).cloned()
+    /// Chords take precedence over the bare key, which is used as a fallback.
+    fn resolve_key(&self, keycode: Keycode, keymod: Mod) -> Option<InputEffect<Axes, Buttons>> {
+        KeyModifier::from_mod(keymod)
+            .and_then(|modifier| self.bindings.get(&InputEvent::KeyChord(modifier, keycode)))
+            .or_else(|| self.bindings.get(&InputEvent::Key(keycode)))
+            .cloned()
+    }
+
+    pub fn resolve_key_down(
+        &mut self,
+        keycode: Keycode,
+        keymod: Mod,
+    ) -> Option<InputEffect<Axes, Buttons>> {
+        let effect = self.resolve_key(keycode, keymod)?;
+        self.held.insert(keycode, effect.clone());
+        Some(effect)
+    }
+
+    /// A key is released as whatever it was pressed as, whichever modifiers
+    /// are held by then.
+    pub fn resolve_key_up(
+        &mut self,
+        keycode: Keycode,
+        keymod: Mod,
+    ) -> Option<InputEffect<Axes, Buttons>> {
+        self.held
+            .remove(&keycode)
+            .or_else(|| self.resolve_key(keycode, keymod))
     }
 
     pub fn resolve_mouse(
@@ -171,3 +240,26 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{Axis, Button};
+    use super::*;
+
+    #[test]
+    fn a_chord_is_released_as_it_was_pressed() {
+        let mut binding = InputBinding::new()
+            .bind_key_to_axis(Keycode::A, Axis::Horz, false)
+            .bind_key_chord_to_button(KeyModifier::Alt, Keycode::A, Button::Num10);
+        let number = Some(InputEffect::Button(Button::Num10, None));
+        let axis = Some(InputEffect::Axis(Axis::Horz, false));
+
+        // Alt is let go before the letter
+        assert_eq!(binding.resolve_key_down(Keycode::A, Mod::LALTMOD), number);
+        assert_eq!(binding.resolve_key_up(Keycode::A, Mod::NOMOD), number);
+
+        // Alt is pressed while the bare letter is held
+        assert_eq!(binding.resolve_key_down(Keycode::A, Mod::NOMOD), axis);
+        assert_eq!(binding.resolve_key_up(Keycode::A, Mod::LALTMOD), axis);
+    }
+}
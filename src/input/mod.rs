@@ -1,5 +1,7 @@
 pub mod events;
 
+pub use self::events::KeyModifier;
+
 use ggez::event::*;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -13,9 +15,64 @@ pub enum Button {
     Num7,
     Num8,
     Num9,
+    Num10,
+    Num11,
+    Num12,
+    Num13,
+    Num14,
+    Num15,
+    Num16,
     Select,
     Delete,
+    Undo,
+    Redo,
+    Notes,
+    ToggleNotes,
+    Hint,
+    Ability1,
+    Ability2,
+    Ability3,
+    /// Switches between the shapes an ability can be aimed in.
+    Rotate,
+    Export,
     Exit,
+    /// Sent when the window loses focus rather than bound to a key.
+    Pause,
+}
+
+impl Button {
+    /// The value a number button enters, if it is one.
+    pub fn number(self) -> Option<u8> {
+        match self {
+            Button::Num1 => Some(1),
+            Button::Num2 => Some(2),
+            Button::Num3 => Some(3),
+            Button::Num4 => Some(4),
+            Button::Num5 => Some(5),
+            Button::Num6 => Some(6),
+            Button::Num7 => Some(7),
+            Button::Num8 => Some(8),
+            Button::Num9 => Some(9),
+            Button::Num10 => Some(10),
+            Button::Num11 => Some(11),
+            Button::Num12 => Some(12),
+            Button::Num13 => Some(13),
+            Button::Num14 => Some(14),
+            Button::Num15 => Some(15),
+            Button::Num16 => Some(16),
+            _ => None,
+        }
+    }
+
+    /// The slot an ability button uses, counting from zero, if it is one.
+    pub fn ability(self) -> Option<usize> {
+        match self {
+            Button::Ability1 => Some(0),
+            Button::Ability2 => Some(1),
+            Button::Ability3 => Some(2),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -48,9 +105,30 @@ pub fn create_input_binding() -> InputBinding {
         .bind_key_to_button(Keycode::Num7, Button::Num7)
         .bind_key_to_button(Keycode::Num8, Button::Num8)
         .bind_key_to_button(Keycode::Num9, Button::Num9)
+        // Values above nine are written as letters, but the bare letters are
+        // taken by movement and other actions.
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::A, Button::Num10)
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::B, Button::Num11)
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::C, Button::Num12)
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::D, Button::Num13)
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::E, Button::Num14)
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::F, Button::Num15)
+        .bind_key_chord_to_button(KeyModifier::Alt, Keycode::G, Button::Num16)
         .bind_key_to_button(Keycode::Escape, Button::Exit)
+        .bind_key_to_button(Keycode::Return, Button::Select)
         .bind_key_to_button(Keycode::Delete, Button::Delete)
         .bind_key_to_button(Keycode::Backspace, Button::Delete)
+        .bind_key_to_button(Keycode::LShift, Button::Notes)
+        .bind_key_to_button(Keycode::RShift, Button::Notes)
+        .bind_key_to_button(Keycode::N, Button::ToggleNotes)
+        .bind_key_to_button(Keycode::H, Button::Hint)
+        .bind_key_to_button(Keycode::Q, Button::Ability1)
+        .bind_key_to_button(Keycode::E, Button::Ability2)
+        .bind_key_to_button(Keycode::R, Button::Ability3)
+        .bind_key_to_button(Keycode::Tab, Button::Rotate)
+        .bind_key_chord_to_button(KeyModifier::Ctrl, Keycode::Z, Button::Undo)
+        .bind_key_chord_to_button(KeyModifier::Ctrl, Keycode::Y, Button::Redo)
+        .bind_key_chord_to_button(KeyModifier::Ctrl, Keycode::E, Button::Export)
         .bind_mouse_to_button(MouseButton::Left, Button::Select)
         .bind_mouse_motion()
 }
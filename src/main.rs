@@ -6,6 +6,10 @@ extern crate ggez;
 extern crate ggez_goodies;
 #[macro_use]
 extern crate log;
+extern crate ron;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 extern crate specs;
 extern crate sudoku;
 // #[macro_use]
@@ -15,7 +19,10 @@ extern crate warmy;
 mod common;
 mod components;
 mod input;
+mod options;
 mod scenes;
+mod settings;
+mod statistics;
 mod systems;
 mod world;
 
@@ -27,19 +34,25 @@ use ggez::event::*;
 use ggez::graphics;
 use ggez::timer;
 
+use options::Options;
+use scenes::export::{self, ExportFormat};
+use scenes::{import, Gameboard};
 use std::path;
 
+/// Scenes are updated a fixed number of times per second, so they can keep
+/// time by counting updates.
+pub const DESIRED_FPS: u32 = 60;
+
 pub struct MainState {
     scenes: scenes::FSceneStack,
     input_binding: input::InputBinding,
 }
 
 impl MainState {
-    pub fn new(resource_dir: &Option<path::PathBuf>, ctx: &mut Context) -> Self {
+    pub fn new(resource_dir: &Option<path::PathBuf>, options: &Options, ctx: &mut Context) -> Self {
         let world = world::World::new(ctx, resource_dir.clone());
         let mut scenestack = scenes::FSceneStack::new(ctx, world);
-        let initial_scene = Box::new(scenes::GameboardScene::new(ctx, &mut scenestack.world));
-        scenestack.push(initial_scene);
+        scenestack.push(Box::new(scenes::TitleScene::new(options.clone())));
         MainState {
             scenes: scenestack,
             input_binding: input::create_input_binding(),
@@ -49,7 +62,6 @@ impl MainState {
 
 impl EventHandler for MainState {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        const DESIRED_FPS: u32 = 60;
         while timer::check_update_time(ctx, DESIRED_FPS) {
             self.scenes.update();
         }
@@ -65,20 +77,14 @@ impl EventHandler for MainState {
         Ok(())
     }
 
-    fn key_down_event(
-        &mut self,
-        _ctx: &mut Context,
-        keycode: Keycode,
-        _keymod: Mod,
-        _repeat: bool,
-    ) {
-        if let Some(ev) = self.input_binding.resolve_key(keycode) {
+    fn key_down_event(&mut self, _ctx: &mut Context, keycode: Keycode, keymod: Mod, _repeat: bool) {
+        if let Some(ev) = self.input_binding.resolve_key_down(keycode, keymod) {
             self.scenes.input(ev, true);
         }
     }
 
-    fn key_up_event(&mut self, _ctx: &mut Context, keycode: Keycode, _keymod: Mod, _repeat: bool) {
-        if let Some(ev) = self.input_binding.resolve_key(keycode) {
+    fn key_up_event(&mut self, _ctx: &mut Context, keycode: Keycode, keymod: Mod, _repeat: bool) {
+        if let Some(ev) = self.input_binding.resolve_key_up(keycode, keymod) {
             self.scenes.input(ev, false);
         }
     }
@@ -95,6 +101,14 @@ impl EventHandler for MainState {
         }
     }
 
+    /// Losing focus pauses the game, as if the player had pressed pause.
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        if !gained {
+            let ev = input::events::InputEffect::Button(input::Button::Pause, None);
+            self.scenes.input(ev, true);
+        }
+    }
+
     fn mouse_motion_event(
         &mut self,
         _ctx: &mut Context,
@@ -110,6 +124,21 @@ impl EventHandler for MainState {
     }
 }
 
+/// The board the command line names, in `format`. A board dealt from a
+/// fresh seed couldn't be dealt again, so one is only exported when it comes
+/// from a seed or a puzzle file.
+fn export_board(options: &Options, format: ExportFormat) -> Result<String, failure::Error> {
+    let gameboard = if let Some(ref path) = options.import {
+        import::load(path, options.puzzle_index)?
+    } else if let Some(seed) = options.seed {
+        let (order, variant, rules) = (options.order, options.variant, &options.rules);
+        Gameboard::with_seed(seed, order, variant, rules, options.rating)
+    } else {
+        bail!("--export needs --seed or --import to say which board");
+    };
+    Ok(export::export(&gameboard, format, options.export_progress))
+}
+
 pub fn main() {
     common::setup_logger().expect("Could not set up logging!");
     let mut cb = ContextBuilder::new("sudoku", "sudoku")
@@ -130,9 +159,17 @@ pub fn main() {
         cb = cb.add_resource_path(s);
     }
 
-    let ctx = &mut cb.build().unwrap();
+    let options = Options::from_args();
+    if let Some(format) = options.export {
+        match export_board(&options, format) {
+            Ok(board) => print!("{}", board),
+            Err(e) => eprintln!("Could not export a board: {}", e),
+        }
+        return;
+    }
 
-    let state = &mut MainState::new(&cargo_path, ctx);
+    let ctx = &mut cb.build().unwrap();
+    let state = &mut MainState::new(&cargo_path, &options, ctx);
     if let Err(e) = event::run(ctx, state) {
         println!("Error encountered: {}", e);
     } else {
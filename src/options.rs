@@ -0,0 +1,117 @@
+//! Command line options.
+
+use scenes::export::ExportFormat;
+use scenes::{can_deal, Rating, Rule, Variant, DEFAULT_ORDER, MAX_ORDER, MIN_ORDER, THREE_STRIKES};
+use std::env;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// Resume the last saved game instead of starting a new one.
+    pub continue_game: bool,
+    /// Play a puzzle read from this file rather than a generated one.
+    pub import: Option<PathBuf>,
+    /// Which puzzle in the imported file to play, counting from zero.
+    pub puzzle_index: usize,
+    /// Deal the puzzle from this seed instead of a fresh one.
+    pub seed: Option<u32>,
+    /// The width of a section on a newly dealt board.
+    pub order: u8,
+    /// The kind of puzzle to deal.
+    pub variant: Variant,
+    /// Extra rules the puzzle is dealt with.
+    pub rules: Vec<Rule>,
+    /// How hard a newly dealt puzzle should be.
+    pub rating: Rating,
+    /// End the battle after this many mistakes.
+    pub mistake_limit: Option<usize>,
+    /// Count the clock down from this many seconds, losing when it runs out.
+    pub time_limit: Option<f64>,
+    /// Print the board given by `--seed` or `--import` in this format and exit
+    /// instead of playing.
+    pub export: Option<ExportFormat>,
+    /// Include the player's entries in the exported board.
+    pub export_progress: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            continue_game: false,
+            import: None,
+            puzzle_index: 0,
+            seed: None,
+            order: DEFAULT_ORDER,
+            variant: Variant::Classic,
+            rules: vec![],
+            rating: Rating::Hard,
+            mistake_limit: None,
+            time_limit: None,
+            export: None,
+            export_progress: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn from_args() -> Self {
+        let mut options = Options::default();
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--continue" => options.continue_game = true,
+                "--import" => options.import = args.next().map(PathBuf::from),
+                "--puzzle" => match args.next().and_then(|n| n.parse::<usize>().ok()) {
+                    Some(n) if n > 0 => options.puzzle_index = n - 1,
+                    _ => warn!("--puzzle expects a number, starting from 1"),
+                },
+                "--seed" => match args.next().and_then(|n| n.parse::<u32>().ok()) {
+                    Some(seed) => options.seed = Some(seed),
+                    None => warn!("--seed expects a number"),
+                },
+                "--order" => match args.next().and_then(|n| n.parse::<u8>().ok()) {
+                    Some(order) if order >= MIN_ORDER && order <= MAX_ORDER => {
+                        options.order = order
+                    }
+                    _ => warn!(
+                        "--order expects a number from {} to {}",
+                        MIN_ORDER, MAX_ORDER
+                    ),
+                },
+                "--killer" => options.variant = Variant::Killer,
+                "--jigsaw" => options.variant = Variant::Jigsaw,
+                "--diagonals" => options.rules.push(Rule::Diagonals),
+                "--anti-knight" => options.rules.push(Rule::AntiKnight),
+                "--odd-even" => options.rules.push(Rule::OddEven(vec![])),
+                "--rating" => match args.next().and_then(|name| Rating::from_name(&name)) {
+                    Some(rating) => options.rating = rating,
+                    None => warn!("--rating expects one of easy, medium, hard, expert or extreme"),
+                },
+                "--three-strikes" => options.mistake_limit = Some(THREE_STRIKES),
+                "--countdown" => match args.next().and_then(|n| n.parse::<u32>().ok()) {
+                    Some(minutes) if minutes > 0 => {
+                        options.time_limit = Some(f64::from(minutes) * 60.0)
+                    }
+                    _ => warn!("--countdown expects a number of minutes"),
+                },
+                "--export" => {
+                    let format = args.next();
+                    match format.as_ref().and_then(|f| ExportFormat::from_name(f)) {
+                        Some(format) => options.export = Some(format),
+                        None => warn!("--export expects one of line, ascii or svg"),
+                    }
+                }
+                "--progress" => options.export_progress = true,
+                _ => warn!("Ignoring unknown argument {:?}", arg),
+            }
+        }
+        if !can_deal(options.order, &options.rules) {
+            warn!(
+                "No board of order {} can follow all of those rules, dealing without them",
+                options.order
+            );
+            options.rules.clear();
+        }
+        options
+    }
+}
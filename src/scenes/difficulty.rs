@@ -0,0 +1,64 @@
+//! Picks how hard a new game's board should be.
+
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input::{self, events::InputEffect, Button};
+use scenes::menu::Menu;
+use scenes::title::NewGame;
+use scenes::{FSceneSwitch, Rating};
+use world::World;
+
+/// The ratings a board can be dealt for. Extreme boards need techniques the
+/// rater doesn't know, so they can't be aimed for.
+const RATINGS: [Rating; 4] = [Rating::Easy, Rating::Medium, Rating::Hard, Rating::Expert];
+
+pub struct DifficultyScene {
+    /// `None` is the way back to the title.
+    menu: Menu<Option<Rating>>,
+    choice: Option<Option<Rating>>,
+}
+
+impl DifficultyScene {
+    pub fn new(rating: Rating) -> Self {
+        let items = RATINGS
+            .iter()
+            .map(|&rating| (rating.name(), Some(rating)))
+            .chain(Some(("Back", None)))
+            .collect();
+        let mut menu = Menu::new("Difficulty", items);
+        menu.select(Some(rating));
+        DifficultyScene { menu, choice: None }
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for DifficultyScene {
+    fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        match self.choice.take() {
+            Some(Some(rating)) => {
+                gameworld.new_game = Some(NewGame::Deal(rating, None));
+                scene::SceneSwitch::Pop
+            }
+            Some(None) => scene::SceneSwitch::Pop,
+            None => scene::SceneSwitch::None,
+        }
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Difficulty"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        match ev {
+            InputEffect::Button(Button::Exit, _) if started => self.choice = Some(None),
+            _ => {
+                if let Some(choice) = self.menu.input(ev, started) {
+                    self.choice = Some(choice);
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,10 @@
 mod models;
 mod scene;
+mod sounds;
 mod views;
 
+pub use self::models::{
+    can_deal, export, fresh_seed, import, load_ron, write_ron, Gameboard, Rating, Rule, SaveGame,
+    Variant, DEFAULT_ORDER, MAX_ORDER, MIN_ORDER, THREE_STRIKES,
+};
 pub use self::scene::GameboardScene;
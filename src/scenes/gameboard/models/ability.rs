@@ -1,16 +1,125 @@
+use super::gameboard::{Gameboard, Point};
+use super::save::AbilitySave;
 use common::resources;
 use ggez::Context;
 use warmy;
 use world::World;
 
+/// Where the player's ability definitions live in the resources directory.
+pub const ABILITIES_FILE: &str = "/abilities.ron";
+/// Shown for a badge with no image at all.
+const PLACEHOLDER_BADGE: &str = "/images/badges/placeholder.png";
+
+/// What an ability does when it is used.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum Effect {
+    /// Fills in this many empty or wrong cells from the solution.
+    RevealNumbers(usize),
+    /// Marks the wrong entries in the targeted cells for as long as the
+    /// ability is active.
+    CheckRegion,
+    /// Stops the clock and the opponent for as long as the ability is active.
+    TimeWarp,
+}
+
+/// What the player has to pick on the board before an ability takes effect.
+#[derive(Debug, Copy, Clone, PartialEq, Deserialize)]
+pub enum Targeting {
+    None,
+    Cell,
+    Row,
+    Column,
+    Box,
+    /// A box, row or column, switched between while aiming.
+    Unit,
+}
+
+impl Targeting {
+    /// The shapes the player can switch between while aiming, starting with
+    /// the one aimed first.
+    pub fn shapes(self) -> Vec<Targeting> {
+        match self {
+            Targeting::Unit => vec![Targeting::Box, Targeting::Row, Targeting::Column],
+            other => vec![other],
+        }
+    }
+
+    /// The cells targeted by picking `point`. A unit is aimed in whichever
+    /// of its shapes comes first.
+    pub fn area(self, board: &Gameboard, point: Point) -> Vec<Point> {
+        let Point(x, y) = point;
+        let points = board.points().into_iter();
+        match self {
+            Targeting::None => vec![],
+            Targeting::Unit => self.shapes()[0].area(board, point),
+            Targeting::Cell => vec![point],
+            Targeting::Row => points.filter(|&Point(_, other)| other == y).collect(),
+            Targeting::Column => points.filter(|&Point(other, _)| other == x).collect(),
+            Targeting::Box => {
+                let regions = board.rules.regions();
+                regions.region(regions.region_of(point))
+            }
+        }
+    }
+}
+
+impl Default for Targeting {
+    fn default() -> Self {
+        Targeting::None
+    }
+}
+
+/// One ability as given in the abilities file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AbilityDefinition {
+    pub name: String,
+    /// The name its badge images start with, such as `"Badge1"`.
+    pub badge: String,
+    #[serde(default)]
+    pub cost: u32,
+    /// Seconds the ability stays active once used.
+    #[serde(default)]
+    pub duration: f32,
+    /// Seconds it takes to come back into stock once it wears off.
+    pub cooldown: f32,
+    #[serde(default)]
+    pub targeting: Targeting,
+    pub effect: Effect,
+}
+
+/// Loads the ability definitions, which warmy reloads whenever the file
+/// changes.
+pub fn ability_definitions(
+    ctx: &mut Context,
+    world: &mut World,
+) -> warmy::Res<resources::Ron<Vec<AbilityDefinition>>> {
+    world
+        .assets
+        .get::<_, resources::Ron<Vec<AbilityDefinition>>>(&warmy::FSKey::new(ABILITIES_FILE), ctx)
+        .unwrap()
+}
+
 #[derive(Debug, Clone)]
 pub struct Ability {
     pub name: String,
     pub status: AbilityStatus,
-    pub icon: warmy::Res<resources::Image>,
+    pub icon_asset: String,
+    pub effect: Effect,
+    pub targeting: Targeting,
+    pub cost: u32,
+    /// Seconds the ability stays active once used.
+    pub duration: f32,
+    /// Seconds it takes to come back into stock once it wears off.
+    pub cooldown: f32,
+    /// Seconds until the status next changes, while active or cooling down.
+    pub remaining: f32,
+    /// Badges missing an image for a status show the inactive one instead.
+    in_stock_icon: Option<warmy::Res<resources::Image>>,
+    active_icon: Option<warmy::Res<resources::Image>>,
+    inactive_icon: warmy::Res<resources::Image>,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub enum AbilityStatus {
     Active,
     Inactive,
@@ -18,19 +127,106 @@ pub enum AbilityStatus {
 }
 
 impl Ability {
-    pub fn new(name: &str, icon_asset: &str, ctx: &mut Context, world: &mut World) -> Self {
-        let status = AbilityStatus::InStock;
-        Ability {
-            name: name.to_string(),
-            status,
-            icon: world
+    pub fn new(definition: &AbilityDefinition, ctx: &mut Context, world: &mut World) -> Self {
+        let mut load = |path: String| {
+            world
                 .assets
-                .get::<_, resources::Image>(
-                    &warmy::FSKey::new(ability_asset_path(icon_asset, &status)),
-                    ctx,
-                ).unwrap(),
+                .get::<_, resources::Image>(&warmy::FSKey::new(path), ctx)
+        };
+        let mut icon = |status| load(ability_asset_path(&definition.badge, &status)).ok();
+        let in_stock_icon = icon(AbilityStatus::InStock);
+        let active_icon = icon(AbilityStatus::Active);
+        let inactive_icon = icon(AbilityStatus::Inactive)
+            .unwrap_or_else(|| load(PLACEHOLDER_BADGE.to_string()).unwrap());
+        Ability {
+            name: definition.name.clone(),
+            status: AbilityStatus::InStock,
+            icon_asset: definition.badge.clone(),
+            effect: definition.effect.clone(),
+            targeting: definition.targeting,
+            cost: definition.cost,
+            duration: definition.duration,
+            cooldown: definition.cooldown,
+            remaining: 0.0,
+            in_stock_icon,
+            active_icon,
+            inactive_icon,
         }
     }
+
+    /// The badge for the current status.
+    pub fn icon(&self) -> &warmy::Res<resources::Image> {
+        self.own_icon().unwrap_or(&self.inactive_icon)
+    }
+
+    pub fn inactive_icon(&self) -> &warmy::Res<resources::Image> {
+        &self.inactive_icon
+    }
+
+    /// Whether the badge has an image of its own for the current status.
+    pub fn has_own_icon(&self) -> bool {
+        self.own_icon().is_some()
+    }
+
+    fn own_icon(&self) -> Option<&warmy::Res<resources::Image>> {
+        match self.status {
+            AbilityStatus::InStock => self.in_stock_icon.as_ref(),
+            AbilityStatus::Active => self.active_icon.as_ref(),
+            AbilityStatus::Inactive => Some(&self.inactive_icon),
+        }
+    }
+
+    pub fn is_in_stock(&self) -> bool {
+        self.status == AbilityStatus::InStock
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.status == AbilityStatus::Active
+    }
+
+    /// Uses the ability if it is in stock. Returns whether it was used.
+    pub fn activate(&mut self) -> bool {
+        if !self.is_in_stock() {
+            return false;
+        }
+        self.status = AbilityStatus::Active;
+        self.remaining = self.duration;
+        true
+    }
+
+    /// Runs the clock on an active or cooling down ability, moving it on to
+    /// its next status when time is up.
+    pub fn update(&mut self, delta: f32) {
+        if self.status == AbilityStatus::InStock {
+            return;
+        }
+        self.remaining -= delta;
+        while self.remaining <= 0.0 && self.status != AbilityStatus::InStock {
+            if self.status == AbilityStatus::Active {
+                self.status = AbilityStatus::Inactive;
+                self.remaining += self.cooldown;
+            } else {
+                self.status = AbilityStatus::InStock;
+                self.remaining = 0.0;
+            }
+        }
+    }
+
+    pub fn snapshot(&self) -> AbilitySave {
+        AbilitySave {
+            name: self.name.clone(),
+            icon_asset: self.icon_asset.clone(),
+            status: self.status,
+            remaining: self.remaining,
+        }
+    }
+
+    /// Picks up where a saved ability left off. What the ability does always
+    /// comes from its definition, not the save.
+    pub fn restore(&mut self, save: &AbilitySave) {
+        self.status = save.status;
+        self.remaining = save.remaining;
+    }
 }
 
 fn ability_asset_path(name: &str, status: &AbilityStatus) -> String {
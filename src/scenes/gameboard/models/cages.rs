@@ -0,0 +1,198 @@
+//! Killer sudoku cages: groups of cells whose values must add up to a target,
+//! with no value repeated inside a cage.
+
+use super::gameboard::Point;
+use super::generator::Random;
+use super::grid::Grid;
+use super::units;
+use sudoku::Element;
+
+/// The most cells a generated cage will have.
+const MAX_CAGE_SIZE: usize = 4;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cage {
+    pub cells: Vec<Point>,
+    pub sum: u32,
+}
+
+/// The smallest and largest sums that `cells` different values, none above
+/// `dimension`, can make.
+pub fn sum_range(cells: u32, dimension: u32) -> (u32, u32) {
+    let lowest = cells * (cells + 1) / 2;
+    let highest = (cells * dimension).saturating_sub(cells * cells.saturating_sub(1) / 2);
+    (lowest, highest)
+}
+
+impl Cage {
+    /// Whether the cage fits on a board of this `dimension` without repeating
+    /// a value, and its sum can be made from that many values.
+    pub fn is_possible(&self, dimension: u8) -> bool {
+        let size = self.cells.len() as u32;
+        let (lowest, highest) = sum_range(size, u32::from(dimension));
+        size <= u32::from(dimension) && lowest <= self.sum && self.sum <= highest
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.cells.contains(&point)
+    }
+
+    /// The top left cell, where the sum is written.
+    pub fn anchor(&self) -> Point {
+        *self
+            .cells
+            .iter()
+            .min_by_key(|&&Point(x, y)| (y, x))
+            .unwrap()
+    }
+
+    /// Whether the values entered so far already overshoot the sum, or fill
+    /// the cage without reaching it. Repeated values are checked separately.
+    pub fn is_broken(&self, grid: &Grid) -> bool {
+        let values: Vec<u32> = self
+            .cells
+            .iter()
+            .filter_map(|&point| grid[point].map(|Element(value)| u32::from(value)))
+            .collect();
+        let total: u32 = values.iter().sum();
+        if values.len() == self.cells.len() {
+            total != self.sum
+        } else {
+            total >= self.sum
+        }
+    }
+
+    /// The outline of the cage as line segments, in cells from the top left
+    /// corner of the board. It runs `inset` cells inside the cage's edge so it
+    /// doesn't sit on top of the grid lines.
+    pub fn outline(&self, inset: f32) -> Vec<((f32, f32), (f32, f32))> {
+        let inside = |x: i32, y: i32| x >= 0 && y >= 0 && self.contains(Point(x as u8, y as u8));
+        let mut segments = vec![];
+        for &Point(x, y) in &self.cells {
+            let (x, y) = (i32::from(x), i32::from(y));
+            // Each side as its outward normal and the direction along it
+            for &((nx, ny), (tx, ty)) in &[
+                ((0, -1), (1, 0)),
+                ((1, 0), (0, 1)),
+                ((0, 1), (-1, 0)),
+                ((-1, 0), (0, -1)),
+            ] {
+                if inside(x + nx, y + ny) {
+                    continue;
+                }
+                // An end stops short at an outside corner, runs on to meet
+                // the next cell's side on a straight edge, and overshoots to
+                // meet it at an inside corner.
+                let end = |s: i32| {
+                    let overshoot = if !inside(x + s * tx, y + s * ty) {
+                        -inset
+                    } else if inside(x + s * tx + nx, y + s * ty + ny) {
+                        inset
+                    } else {
+                        0.0
+                    };
+                    let along = s as f32 * (0.5 + overshoot);
+                    let across = 0.5 - inset;
+                    (
+                        x as f32 + 0.5 + nx as f32 * across + tx as f32 * along,
+                        y as f32 + 0.5 + ny as f32 * across + ty as f32 * along,
+                    )
+                };
+                segments.push((end(-1), end(1)));
+            }
+        }
+        segments
+    }
+}
+
+/// Splits a solved grid into cages of neighbouring cells, none of which
+/// repeat a value.
+pub fn random_cages(solution: &Grid, random: &mut Random) -> Vec<Cage> {
+    let dimension = solution.dimension();
+    let mut caged = vec![false; solution.points().len()];
+    let offset = |Point(x, y): Point| y as usize * dimension as usize + x as usize;
+    let mut starts = solution.points();
+    random.shuffle(&mut starts);
+
+    let mut cages = vec![];
+    for start in starts {
+        if caged[offset(start)] {
+            continue;
+        }
+        let size = 1 + random.below(MAX_CAGE_SIZE.min(dimension as usize - 1));
+        let mut cells = vec![start];
+        caged[offset(start)] = true;
+        while cells.len() < size {
+            let mut candidates: Vec<Point> = cells
+                .iter()
+                .flat_map(|&point| units::neighbours(point, dimension))
+                .filter(|&point| !caged[offset(point)])
+                .filter(|&point| cells.iter().all(|&cell| solution[cell] != solution[point]))
+                .collect();
+            if candidates.is_empty() {
+                break;
+            }
+            candidates.sort_by_key(|&Point(x, y)| (y, x));
+            candidates.dedup();
+            let next = candidates[random.below(candidates.len())];
+            caged[offset(next)] = true;
+            cells.push(next);
+        }
+        let sum = cells
+            .iter()
+            .filter_map(|&point| solution[point].map(|Element(value)| u32::from(value)))
+            .sum();
+        cages.push(Cage { cells, sum });
+    }
+    cages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::RuleSet;
+    use super::super::solver;
+    use super::*;
+
+    fn cage(sum: u32) -> Cage {
+        Cage {
+            cells: vec![Point(0, 0), Point(1, 0)],
+            sum,
+        }
+    }
+
+    #[test]
+    fn sums_range_over_distinct_values() {
+        assert_eq!(sum_range(1, 9), (1, 9));
+        assert_eq!(sum_range(2, 9), (3, 17));
+        assert_eq!(sum_range(4, 4), (10, 10));
+        assert!(cage(17).is_possible(9));
+        assert!(!cage(18).is_possible(9));
+        assert!(!cage(2).is_possible(9));
+    }
+
+    #[test]
+    fn breaks_on_overshooting_or_missing_the_sum() {
+        let mut grid = Grid::empty(3);
+        grid.set(Point(0, 0), Some(Element(5)));
+        assert!(cage(5).is_broken(&grid));
+        assert!(!cage(6).is_broken(&grid));
+
+        grid.set(Point(1, 0), Some(Element(1)));
+        assert!(!cage(6).is_broken(&grid));
+        assert!(cage(7).is_broken(&grid));
+    }
+
+    #[test]
+    fn solutions_keep_to_the_cages() {
+        let values = [0, 0, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 1];
+        let grid = Grid::from_values(2, &values).unwrap();
+        let rules = RuleSet::classic(2);
+        let count = |sum| {
+            solver::solutions_within(&grid, &rules, &[cage(sum)], 2, 100)
+                .unwrap()
+                .len()
+        };
+        assert_eq!(count(3), 1);
+        assert_eq!(count(4), 0);
+    }
+}
@@ -1,21 +1,75 @@
 use super::ability::Ability;
+use super::save::{AbilitySave, CharacterSave};
 use common::resources;
 use ggez::Context;
 use warmy;
 use world::World;
 
-#[derive(Debug, Copy, Clone)]
+/// Damage a correct placement deals the opponent, before its defense.
+pub const PLACEMENT_ATTACK: u32 = 10;
+/// Damage a wrong placement deals the player, before their defense.
+pub const MISTAKE_ATTACK: u32 = 25;
+pub const DEFAULT_MAX_HP: u32 = 100;
+/// Energy the player gains for a correct placement.
+pub const PLACEMENT_ENERGY: u32 = 5;
+/// Energy the player gains for each row, column or box a placement completes.
+pub const COMPLETION_ENERGY: u32 = 15;
+pub const DEFAULT_MAX_ENERGY: u32 = 100;
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub enum CharacterKind {
     Character,
     Opponent,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub hp: u32,
+    pub max_hp: u32,
+    /// Taken off the damage of every hit.
+    pub defense: u32,
+    /// Spent on abilities. Saves from before energy start with none.
+    #[serde(default)]
+    pub energy: u32,
+    #[serde(default = "default_max_energy")]
+    pub max_energy: u32,
+}
+
+fn default_max_energy() -> u32 {
+    DEFAULT_MAX_ENERGY
+}
+
+impl Stats {
+    pub fn new(max_hp: u32, defense: u32) -> Self {
+        Stats {
+            hp: max_hp,
+            max_hp,
+            defense,
+            energy: 0,
+            max_energy: DEFAULT_MAX_ENERGY,
+        }
+    }
+
+    /// The damage a hit of `attack` does after defense. Every hit does at
+    /// least one point.
+    pub fn damage(&self, attack: u32) -> u32 {
+        attack.saturating_sub(self.defense).max(1)
+    }
+
+    /// Adds `energy`, up to the most that can be held.
+    pub fn gain_energy(&mut self, energy: u32) {
+        self.energy = (self.energy + energy).min(self.max_energy);
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Character {
     pub name: String,
     pub kind: CharacterKind,
+    pub image_asset: String,
     pub image: warmy::Res<resources::Image>,
     pub abilities: Vec<Ability>,
+    pub stats: Stats,
 }
 
 impl Character {
@@ -29,6 +83,7 @@ impl Character {
         Character {
             name: name.to_string(),
             kind,
+            image_asset: image_asset.to_string(),
             image: world
                 .assets
                 .get::<_, resources::Image>(
@@ -36,11 +91,116 @@ impl Character {
                     ctx,
                 ).unwrap(),
             abilities: vec![],
+            stats: Stats::new(DEFAULT_MAX_HP, 0),
         }
     }
 
+    pub fn with_stats(mut self, max_hp: u32, defense: u32) -> Self {
+        self.stats = Stats::new(max_hp, defense);
+        self
+    }
+
     pub fn add_ability(mut self, ability: Ability) -> Self {
         self.abilities.push(ability);
         self
     }
+
+    /// Swaps in new definitions of the character's abilities, each keeping
+    /// where the old one of the same name was in its cooldown.
+    pub fn replace_abilities(&mut self, abilities: Vec<Ability>) {
+        let saved: Vec<AbilitySave> = self.abilities.iter().map(Ability::snapshot).collect();
+        self.abilities = abilities;
+        self.resume_abilities(&saved);
+    }
+
+    fn resume_abilities(&mut self, saved: &[AbilitySave]) {
+        for ability in &mut self.abilities {
+            if let Some(saved) = saved.iter().find(|saved| saved.name == ability.name) {
+                ability.restore(saved);
+            }
+        }
+    }
+
+    /// Returns the damage done.
+    pub fn take_hit(&mut self, attack: u32) -> u32 {
+        let damage = self.stats.damage(attack).min(self.stats.hp);
+        self.stats.hp -= damage;
+        damage
+    }
+
+    /// Back to full HP and no energy, for starting the battle over.
+    pub fn heal(&mut self) {
+        self.stats.hp = self.stats.max_hp;
+        self.stats.energy = 0;
+    }
+
+    pub fn gain_energy(&mut self, energy: u32) {
+        self.stats.gain_energy(energy);
+    }
+
+    /// Whether `ability` is in stock and there is enough energy to pay for it.
+    pub fn can_use(&self, ability: &Ability) -> bool {
+        ability.is_in_stock() && ability.cost <= self.stats.energy
+    }
+
+    /// Uses the ability in `slot` if it can be, paying for it. Returns
+    /// whether it was used.
+    pub fn activate_ability(&mut self, slot: usize) -> bool {
+        let energy = self.stats.energy;
+        let ability = match self.abilities.get_mut(slot) {
+            Some(ability) if ability.cost <= energy => ability,
+            _ => return false,
+        };
+        if !ability.activate() {
+            return false;
+        }
+        self.stats.energy -= ability.cost;
+        true
+    }
+
+    pub fn is_defeated(&self) -> bool {
+        self.stats.hp == 0
+    }
+
+    pub fn snapshot(&self) -> CharacterSave {
+        CharacterSave {
+            name: self.name.clone(),
+            kind: self.kind,
+            image_asset: self.image_asset.clone(),
+            abilities: self.abilities.iter().map(Ability::snapshot).collect(),
+            stats: Some(self.stats),
+        }
+    }
+
+    /// Rebuilds a saved character with `abilities`, each picking up where
+    /// the saved ability of the same name left off.
+    pub fn restore(
+        save: &CharacterSave,
+        abilities: Vec<Ability>,
+        ctx: &mut Context,
+        world: &mut World,
+    ) -> Self {
+        let mut character = Self::new(&save.name, save.kind, &save.image_asset, ctx, world);
+        character.abilities = abilities;
+        character.resume_abilities(&save.abilities);
+        // Saves from before combat have no stats
+        if let Some(stats) = save.stats {
+            character.stats = stats;
+        }
+        character
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn energy_stops_at_the_most_that_can_be_held() {
+        let mut stats = Stats::new(DEFAULT_MAX_HP, 0);
+        stats.gain_energy(PLACEMENT_ENERGY + 2 * COMPLETION_ENERGY);
+        assert_eq!(stats.energy, 35);
+        stats.gain_energy(DEFAULT_MAX_ENERGY);
+        assert_eq!(stats.energy, DEFAULT_MAX_ENERGY);
+    }
 }
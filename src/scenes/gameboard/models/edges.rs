@@ -0,0 +1,64 @@
+//! Where lines go between cells. The board view and exported images both
+//! draw from this, so a printed puzzle looks like the one on screen.
+
+use super::gameboard::Point;
+use super::regions::Regions;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EdgeKind {
+    Cell,
+    Section,
+}
+
+/// A straight line between cells, measured in cells from the top left
+/// corner of the board.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub kind: EdgeKind,
+    pub from: (u8, u8),
+    pub to: (u8, u8),
+}
+
+/// The lines inside a board. Lines between cells in different regions are
+/// section edges. The outer border is left to whatever the board is drawn on.
+pub fn edges(regions: &Regions) -> Vec<Edge> {
+    let dimension = regions.order() * regions.order();
+    let kind = |a: Point, b: Point| {
+        if regions.region_of(a) == regions.region_of(b) {
+            EdgeKind::Cell
+        } else {
+            EdgeKind::Section
+        }
+    };
+    let mut edges = vec![];
+    for i in 1..dimension {
+        let vertical = (0..dimension)
+            .map(|j| kind(Point(i - 1, j), Point(i, j)))
+            .collect();
+        push_line(&mut edges, vertical, |j| (i, j));
+        let horizontal = (0..dimension)
+            .map(|j| kind(Point(j, i - 1), Point(j, i)))
+            .collect();
+        push_line(&mut edges, horizontal, |j| (j, i));
+    }
+    edges
+}
+
+/// Joins a line's one-cell pieces into as few edges as possible. `at` gives
+/// the point a given number of cells along the line.
+fn push_line<F>(edges: &mut Vec<Edge>, kinds: Vec<EdgeKind>, at: F)
+where
+    F: Fn(u8) -> (u8, u8),
+{
+    let mut start = 0;
+    for end in 1..=kinds.len() {
+        if end == kinds.len() || kinds[end] != kinds[start] {
+            edges.push(Edge {
+                kind: kinds[start],
+                from: at(start as u8),
+                to: at(end as u8),
+            });
+            start = end;
+        }
+    }
+}
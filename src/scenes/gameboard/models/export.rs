@@ -0,0 +1,326 @@
+//! Writing boards out as text or pictures, for printing and for pasting into
+//! bug reports. Each form can show either the puzzle as it was dealt or the
+//! player's progress on it.
+
+use super::edges::EdgeKind;
+use super::gameboard::{Gameboard, Point};
+use super::grid::{self, Grid};
+use super::regions::Regions;
+use super::rules::{Parity, Rule};
+use chrono;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use sudoku::Element;
+
+const SVG_SIZE: f32 = 400.0;
+const SVG_MARGIN: f32 = 10.0;
+const SVG_CELL_EDGE_WIDTH: f32 = 1.0;
+const SVG_SECTION_EDGE_WIDTH: f32 = 4.0;
+const SVG_GIVEN_BACKGROUND: &str = "#808080";
+const SVG_CAGE_INSET: f32 = 0.08;
+const SVG_CAGE_COLOR: &str = "#4d4d4d";
+const SVG_PARITY_COLOR: &str = "#d9d9d9";
+const SVG_PARITY_SIZE: f32 = 0.8;
+const SVG_DIAGONAL_COLOR: &str = "#3366e6";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One character per cell on a single line, as read by `import`.
+    Line,
+    /// A grid drawn with `+`, `-` and `|`.
+    Ascii,
+    Svg,
+}
+
+impl ExportFormat {
+    pub fn all() -> [ExportFormat; 3] {
+        [ExportFormat::Line, ExportFormat::Ascii, ExportFormat::Svg]
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "line" => Some(ExportFormat::Line),
+            "ascii" => Some(ExportFormat::Ascii),
+            "svg" => Some(ExportFormat::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Line => "sdm",
+            ExportFormat::Ascii => "txt",
+            ExportFormat::Svg => "svg",
+        }
+    }
+}
+
+pub fn export(board: &Gameboard, format: ExportFormat, with_progress: bool) -> String {
+    let grid = if with_progress {
+        &board.current
+    } else {
+        board.problem()
+    };
+    match format {
+        ExportFormat::Line => to_line(grid),
+        ExportFormat::Ascii if board.rules.regions().is_square() => to_ascii(grid),
+        ExportFormat::Ascii => to_ascii_jigsaw(grid, board.rules.regions()),
+        ExportFormat::Svg => to_svg(board, grid),
+    }
+}
+
+/// Writes every format, with and without progress, into a new folder under
+/// `dir`. Returns the folder.
+pub fn export_all(board: &Gameboard, dir: &Path) -> io::Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y-%m-%d_%H-%M-%S");
+    let dir = dir.join("exports").join(timestamp.to_string());
+    fs::create_dir_all(&dir)?;
+    for &format in ExportFormat::all().iter() {
+        for &(name, with_progress) in &[("puzzle", false), ("progress", true)] {
+            let path = dir.join(format!("{}.{}", name, format.extension()));
+            fs::write(path, export(board, format, with_progress))?;
+        }
+    }
+    Ok(dir)
+}
+
+fn glyph(value: Option<Element>) -> char {
+    match value {
+        Some(Element(value)) => grid::glyph(value),
+        None => '.',
+    }
+}
+
+fn to_line(grid: &Grid) -> String {
+    let mut line: String = grid.points().into_iter().map(|p| glyph(grid[p])).collect();
+    line.push('\n');
+    line
+}
+
+fn to_ascii(grid: &Grid) -> String {
+    let order = grid.order();
+    let dimension = grid.dimension();
+    let section = "-".repeat(order as usize * 2 + 1);
+    let separator = format!("+{}+\n", vec![section; order as usize].join("+"));
+
+    let mut out = String::new();
+    for y in 0..dimension {
+        if y % order == 0 {
+            out.push_str(&separator);
+        }
+        for x in 0..dimension {
+            if x % order == 0 {
+                out.push_str("| ");
+            }
+            out.push(glyph(grid[Point(x, y)]));
+            out.push(' ');
+        }
+        out.push_str("|\n");
+    }
+    out.push_str(&separator);
+    out
+}
+
+/// Irregular regions can't be split into even blocks, so walls are drawn
+/// between individual cells wherever the region changes.
+fn to_ascii_jigsaw(grid: &Grid, regions: &Regions) -> String {
+    let dimension = grid.dimension();
+    let differs = |a: Point, b: Point| regions.region_of(a) != regions.region_of(b);
+
+    let mut out = String::new();
+    for y in 0..=dimension {
+        out.push('+');
+        for x in 0..dimension {
+            let wall = y == 0 || y == dimension || differs(Point(x, y - 1), Point(x, y));
+            out.push_str(if wall { "---+" } else { "   +" });
+        }
+        out.push('\n');
+        if y == dimension {
+            break;
+        }
+        out.push('|');
+        for x in 0..dimension {
+            out.push(' ');
+            out.push(glyph(grid[Point(x, y)]));
+            out.push(' ');
+            let wall = x + 1 == dimension || differs(Point(x, y), Point(x + 1, y));
+            out.push(if wall { '|' } else { ' ' });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn to_svg(board: &Gameboard, grid: &Grid) -> String {
+    let dimension = grid.dimension();
+    let cell_size = SVG_SIZE / f32::from(dimension);
+    let total = SVG_SIZE + SVG_MARGIN * 2.0;
+    let at = |cells: u8| SVG_MARGIN + f32::from(cells) * cell_size;
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+        total
+    ).unwrap();
+    writeln!(out, r#"<rect width="100%" height="100%" fill="white"/>"#).unwrap();
+
+    for point in grid.points() {
+        let Point(x, y) = point;
+        let value = grid[point];
+        if value.is_some() && !board.is_mutable(point) {
+            writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}"/>"#,
+                at(x),
+                at(y),
+                cell_size,
+                SVG_GIVEN_BACKGROUND
+            ).unwrap();
+        }
+        let shade = cell_size * SVG_PARITY_SIZE;
+        let padding = (cell_size - shade) / 2.0;
+        match board.rules.parity(point) {
+            Some(Parity::Odd) => writeln!(
+                out,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="{}"/>"#,
+                at(x) + cell_size / 2.0,
+                at(y) + cell_size / 2.0,
+                shade / 2.0,
+                SVG_PARITY_COLOR
+            ).unwrap(),
+            Some(Parity::Even) => writeln!(
+                out,
+                r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="{3}"/>"#,
+                at(x) + padding,
+                at(y) + padding,
+                shade,
+                SVG_PARITY_COLOR
+            ).unwrap(),
+            None => {}
+        }
+        if value.is_none() {
+            continue;
+        }
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+            at(x) + cell_size / 2.0,
+            at(y) + cell_size / 2.0,
+            cell_size * 0.6,
+            glyph(value)
+        ).unwrap();
+    }
+
+    for cage in &board.cages {
+        for ((x1, y1), (x2, y2)) in cage.outline(SVG_CAGE_INSET) {
+            writeln!(
+                out,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="1" stroke-dasharray="4 4"/>"#,
+                SVG_MARGIN + x1 * cell_size,
+                SVG_MARGIN + y1 * cell_size,
+                SVG_MARGIN + x2 * cell_size,
+                SVG_MARGIN + y2 * cell_size,
+                SVG_CAGE_COLOR
+            ).unwrap();
+        }
+        let Point(x, y) = cage.anchor();
+        let inset = SVG_CAGE_INSET * cell_size;
+        writeln!(
+            out,
+            r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" dominant-baseline="hanging">{}</text>"#,
+            at(x) + inset + 1.0,
+            at(y) + inset + 1.0,
+            cell_size * 0.22,
+            cage.sum
+        ).unwrap();
+    }
+
+    if board.rules.rules().contains(&Rule::Diagonals) {
+        let (start, end) = (SVG_MARGIN, SVG_MARGIN + SVG_SIZE);
+        for &(x1, x2) in &[(start, end), (end, start)] {
+            writeln!(
+                out,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}" stroke-width="3" stroke-opacity="0.35"/>"#,
+                x1, start, x2, end, SVG_DIAGONAL_COLOR
+            ).unwrap();
+        }
+    }
+
+    for edge in board.edges() {
+        let width = match edge.kind {
+            EdgeKind::Cell => SVG_CELL_EDGE_WIDTH,
+            EdgeKind::Section => SVG_SECTION_EDGE_WIDTH,
+        };
+        writeln!(
+            out,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="{}"/>"#,
+            at(edge.from.0),
+            at(edge.from.1),
+            at(edge.to.0),
+            at(edge.to.1),
+            width
+        ).unwrap();
+    }
+    writeln!(
+        out,
+        r#"<rect x="{0}" y="{0}" width="{1}" height="{1}" fill="none" stroke="black" stroke-width="{2}"/>"#,
+        SVG_MARGIN,
+        SVG_SIZE,
+        SVG_SECTION_EDGE_WIDTH
+    ).unwrap();
+    out.push_str("</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUZZLE: [u8; 16] = [1, 2, 0, 4, 0, 0, 1, 2, 0, 1, 4, 3, 4, 3, 2, 0];
+
+    fn board() -> Gameboard {
+        let problem = Grid::from_values(2, &PUZZLE).unwrap();
+        let mut board = Gameboard::import(problem, None, vec![]).unwrap();
+        board.insert(Point(2, 0), Element(3));
+        board
+    }
+
+    #[test]
+    fn writes_a_line_with_or_without_progress() {
+        let board = board();
+        let line = |with_progress| export(&board, ExportFormat::Line, with_progress);
+        assert_eq!(line(false), "12.4..12.143432.\n");
+        assert_eq!(line(true), "1234..12.143432.\n");
+    }
+
+    #[test]
+    fn draws_sections_in_ascii() {
+        let expected = "+-----+-----+\n\
+                        | 1 2 | 3 4 |\n\
+                        | . . | 1 2 |\n\
+                        +-----+-----+\n\
+                        | . 1 | 4 3 |\n\
+                        | 4 3 | 2 . |\n\
+                        +-----+-----+\n";
+        assert_eq!(export(&board(), ExportFormat::Ascii, true), expected);
+    }
+
+    #[test]
+    fn draws_walls_between_jigsaw_regions() {
+        let layout = vec![0, 0, 1, 1, 0, 2, 1, 1, 0, 2, 3, 3, 2, 2, 3, 3];
+        let regions = Regions::from_cells(2, layout).unwrap();
+        let expected = "+---+---+---+---+\n\
+                        | .   . | .   . |\n\
+                        +   +---+   +   +\n\
+                        | . | . | .   . |\n\
+                        +   +   +---+---+\n\
+                        | . | . | .   . |\n\
+                        +---+   +   +   +\n\
+                        | .   . | .   . |\n\
+                        +---+---+---+---+\n";
+        assert_eq!(to_ascii_jigsaw(&Grid::empty(2), &regions), expected);
+    }
+}
@@ -1,70 +1,556 @@
+use super::cages::Cage;
+use super::edges::{self, Edge};
+use super::generator::{self, Deal, Random};
+use super::grid::Grid;
+use super::history::{History, Move};
+use super::import::ImportError;
+use super::logic::{self, Hint, HintError};
+use super::notes::Notes;
+use super::rating::{self, Rating};
+use super::regions::Regions;
+use super::rules::{Rule, RuleSet};
+use super::save::{BoardSave, SaveError};
+use super::solver;
 use input;
-use sudoku::{self, Element, Generate, Grid, Point as KuPoint, Puzzle, Solve, Sudoku};
+use std::collections::HashSet;
+use sudoku::Element;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Point(pub u8, pub u8);
-impl Into<KuPoint> for Point {
-    fn into(self) -> KuPoint {
-        let Point(x, y) = self;
-        let mut new_point = KuPoint::origin();
-        new_point[0] = x;
-        new_point[1] = y;
-        new_point
-    }
+
+/// The smallest and largest boards we can deal, as the width of a section.
+pub const MIN_ORDER: u8 = 2;
+pub const MAX_ORDER: u8 = 4;
+pub const DEFAULT_ORDER: u8 = 3;
+
+const MAX_RATING_ATTEMPTS: usize = 20;
+/// Boards bigger than the standard one are slow to deal and to rate, so they
+/// get fewer tries at hitting the rating.
+const MAX_RATING_ATTEMPTS_LARGE: usize = 2;
+/// How many guesses to spend checking that an imported puzzle is unique.
+const IMPORT_SOLVER_BUDGET: usize = 1_000_000;
+/// How many mistakes lose the battle when playing with three strikes.
+pub const THREE_STRIKES: usize = 3;
+
+/// The kinds of puzzle that can be dealt.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Variant {
+    Classic,
+    /// Cells are grouped into cages that must add up to a given sum.
+    Killer,
+    /// The regions are irregular shapes instead of square sections.
+    Jigsaw,
 }
 
-const ORDER: u8 = 3;
+impl Variant {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Variant::Classic => "Classic",
+            Variant::Killer => "Killer",
+            Variant::Jigsaw => "Jigsaw",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Gameboard {
-    problem: Sudoku,
-    pub current: Sudoku,
-    pub solution: Sudoku,
+    problem: Grid,
+    pub current: Grid,
+    pub solution: Grid,
+    pub rules: RuleSet,
+    /// Killer sudoku cages. Empty for other kinds of puzzle.
+    pub cages: Vec<Cage>,
+    pub rating: Rating,
+    /// The seed the puzzle was dealt from, if it was generated.
+    pub seed: Option<u32>,
     pub moves: usize,
+    /// Entries that didn't match the solution.
+    pub mistakes: usize,
+    /// Reaching this many mistakes loses the battle. No limit if `None`.
+    pub mistake_limit: Option<usize>,
+    /// Hints that found a step to take.
+    pub hints_used: usize,
+    /// Cells that have already counted as a correct placement, so taking a
+    /// value out and putting it back doesn't count twice.
+    scored: HashSet<Point>,
     pub selected_cell: Option<Point>,
+    pub notes: Notes,
+    pub notes_mode: bool,
+    pub hint: Option<Result<Hint, HintError>>,
+    history: History,
 }
 
 impl Gameboard {
-    pub fn new(difficulty: sudoku::Difficulty) -> Self {
-        let problem = Sudoku::generate(ORDER, difficulty);
+    /// Deals the same puzzle every time for the same seed. Givens are taken
+    /// away at random, so how hard the result is varies; keep dealing from
+    /// the seed until a puzzle needs the techniques of `rating`. After a few
+    /// attempts, settle for the closest one found.
+    ///
+    /// Killer puzzles are dealt once and keep the rating asked for, since the
+    /// logical solver doesn't take cages into account.
+    pub fn with_seed(
+        seed: u32,
+        order: u8,
+        variant: Variant,
+        rules: &[Rule],
+        rating: Rating,
+    ) -> Self {
+        let mut random = Random::new(u64::from(seed));
+        let attempts = if variant == Variant::Killer {
+            1
+        } else if order > DEFAULT_ORDER {
+            MAX_RATING_ATTEMPTS_LARGE
+        } else {
+            MAX_RATING_ATTEMPTS
+        };
+        let mut best: Option<Gameboard> = None;
+        for _ in 0..attempts {
+            let deal = generator::generate(order, variant, rules, rating, &mut random);
+            let mut board = Self::from_deal(deal);
+            board.seed = Some(seed);
+            if !board.is_rated() {
+                board.rating = rating;
+            }
+            if board.rating == rating {
+                return board;
+            }
+            let is_closer = best.as_ref().map_or(true, |best| {
+                rating.distance(board.rating) < rating.distance(best.rating)
+            });
+            if is_closer {
+                best = Some(board);
+            }
+        }
+        best.unwrap()
+    }
+
+    /// Builds a board from a puzzle that came from somewhere other than the
+    /// generator, so it can't be trusted to have exactly one solution.
+    /// Jigsaw puzzles bring their own `regions`; the rest use square sections.
+    pub fn import(
+        problem: Grid,
+        regions: Option<Regions>,
+        cages: Vec<Cage>,
+    ) -> Result<Self, ImportError> {
+        Self::import_within(problem, regions, cages, IMPORT_SOLVER_BUDGET)
+    }
+
+    /// Like `import`, giving up after `budget` guesses.
+    fn import_within(
+        problem: Grid,
+        regions: Option<Regions>,
+        cages: Vec<Cage>,
+        budget: usize,
+    ) -> Result<Self, ImportError> {
+        let mut rules = RuleSet::classic(problem.order());
+        if let Some(regions) = regions {
+            rules.set_regions(regions);
+        }
+        let mut solutions = solver::solutions_within(&problem, &rules, &cages, 2, budget)
+            .ok_or(ImportError::Undecided)?;
+        match solutions.len() {
+            0 => Err(ImportError::NoSolution),
+            1 => Ok(Self::from_deal(Deal {
+                problem,
+                solution: solutions.remove(0),
+                rules,
+                cages,
+            })),
+            _ => Err(ImportError::MultipleSolutions),
+        }
+    }
+
+    fn from_deal(deal: Deal) -> Self {
+        let Deal {
+            problem,
+            solution,
+            rules,
+            cages,
+        } = deal;
         let current = problem.clone();
-        let solution = problem.solution().unwrap();
+        let rating = if cages.is_empty() {
+            rating::rate(&problem, &rules)
+        } else {
+            Rating::Extreme
+        };
+        let notes = Notes::new(problem.order());
         Self {
             problem,
             current,
             solution,
+            rules,
+            cages,
+            rating,
+            seed: None,
             moves: 0,
+            mistakes: 0,
+            mistake_limit: None,
+            hints_used: 0,
+            scored: HashSet::new(),
             selected_cell: None,
+            notes,
+            notes_mode: false,
+            hint: None,
+            history: History::new(),
+        }
+    }
+
+    /// Captures everything about the board needed to pick the game back up.
+    pub fn snapshot(&self) -> BoardSave {
+        BoardSave {
+            order: self.order(),
+            problem: self.problem.values(),
+            current: self.current.values(),
+            solution: self.solution.values(),
+            regions: self.rules.regions().cells().to_vec(),
+            rules: self.rules.rules().to_vec(),
+            cages: self.cages.clone(),
+            rating: self.rating,
+            seed: self.seed,
+            notes: self.notes.clone(),
+            moves: self.moves,
+            mistakes: self.mistakes,
+            mistake_limit: self.mistake_limit,
+            hints_used: self.hints_used,
+            scored: self.scored.iter().cloned().collect(),
+            selected_cell: self.selected_cell,
+        }
+    }
+
+    /// Rebuilds a saved board. The save may have been edited or cut short,
+    /// so anything that doesn't fit on a board of its order makes it
+    /// `Corrupt` rather than panicking later.
+    pub fn restore(save: BoardSave) -> Result<Self, SaveError> {
+        let order = save.order;
+        if order < MIN_ORDER || order > MAX_ORDER {
+            return Err(SaveError::Corrupt);
+        }
+        let dimension = order * order;
+        let grid = |values: &[u8]| {
+            if values.iter().any(|&value| value > dimension) {
+                return Err(SaveError::Corrupt);
+            }
+            Grid::from_values(order, values).ok_or(SaveError::Corrupt)
+        };
+        let on_board = |&Point(x, y): &Point| x < dimension && y < dimension;
+        let rule_cells = save.rules.iter().flat_map(|rule| match *rule {
+            Rule::OddEven(ref cells) => cells.iter().map(|&(point, _)| point).collect(),
+            _ => vec![],
+        });
+        let cage_cells = save
+            .cages
+            .iter()
+            .flat_map(|cage| cage.cells.iter().cloned());
+        let points: Vec<Point> = save
+            .selected_cell
+            .into_iter()
+            .chain(save.scored.iter().cloned())
+            .chain(cage_cells)
+            .chain(rule_cells)
+            .collect();
+        if !points.iter().all(on_board) || !save.notes.fits(order) {
+            return Err(SaveError::Corrupt);
+        }
+        // Saves from before jigsaw puzzles have no regions
+        let regions = if save.regions.is_empty() {
+            Regions::square(order)
+        } else {
+            Regions::from_cells(order, save.regions).ok_or(SaveError::Corrupt)?
+        };
+        Ok(Self {
+            problem: grid(&save.problem)?,
+            current: grid(&save.current)?,
+            solution: grid(&save.solution)?,
+            rules: RuleSet::new(regions, save.rules),
+            cages: save.cages,
+            rating: save.rating,
+            seed: save.seed,
+            moves: save.moves,
+            mistakes: save.mistakes,
+            mistake_limit: save.mistake_limit,
+            hints_used: save.hints_used,
+            scored: save.scored.into_iter().collect(),
+            selected_cell: save.selected_cell,
+            notes: save.notes,
+            notes_mode: false,
+            hint: None,
+            history: History::new(),
+        })
+    }
+
+    /// Cells that break the rule set, cells whose value is repeated
+    /// elsewhere in the same cage, and every cell of a cage that can no
+    /// longer make its sum. This only looks at the rules of the game, never
+    /// the solution.
+    pub fn conflicts(&self) -> HashSet<Point> {
+        let mut conflicts = self.rules.conflicts(&self.current);
+        for cage in &self.cages {
+            if cage.is_broken(&self.current) {
+                conflicts.extend(cage.cells.iter().cloned());
+            }
+            for (i, &point) in cage.cells.iter().enumerate() {
+                let value = match self.current[point] {
+                    Some(value) => value,
+                    None => continue,
+                };
+                for &other in &cage.cells[i + 1..] {
+                    if self.current[other] == Some(value) {
+                        conflicts.insert(point);
+                        conflicts.insert(other);
+                    }
+                }
+            }
         }
+        conflicts
+    }
+
+    /// Looks for the next logical step from the current state of the board.
+    /// Conflicting entries have to be cleared up first, since any deduction
+    /// made from them would be meaningless.
+    pub fn request_hint(&mut self) {
+        let hint = if !self.is_rated() {
+            Err(HintError::Cages)
+        } else if self.conflicts().is_empty() {
+            logic::find_hint(&self.current, &self.solution, &self.rules)
+        } else {
+            Err(HintError::Conflicts)
+        };
+        if hint.is_ok() {
+            self.hints_used += 1;
+        }
+        self.hint = Some(hint);
     }
 
     pub fn insertion_is_correct(&self, point: Point, value: Element) -> bool {
-        self.solution[point.into()] == Some(value)
+        self.solution[point] == Some(value)
+    }
+
+    /// Whether a correct value at `point` is counted for the first time.
+    pub fn score(&mut self, point: Point) -> bool {
+        self.scored.insert(point)
+    }
+
+    pub fn record_mistake(&mut self) {
+        self.mistakes += 1;
+    }
+
+    /// Puts the board back the way it was dealt, keeping the mistake limit.
+    pub fn restart(&mut self) {
+        self.current = self.problem.clone();
+        self.notes = Notes::new(self.order());
+        self.moves = 0;
+        self.mistakes = 0;
+        self.hints_used = 0;
+        self.scored.clear();
+        self.selected_cell = None;
+        self.hint = None;
+        self.history = History::new();
     }
 
+    /// Places `value` at `point`, and clears it from the notes of every cell
+    /// that can no longer hold it.
     pub fn insert(&mut self, point: Point, value: Element) {
-        self.current = self.current.substitute(point.into(), Some(value));
-        self.moves += 1;
+        self.set(point, Some(value));
+        self.clear_notes(point, value);
+    }
+
+    /// Takes the notes out of `point`, and `value` out of the notes of every
+    /// cell that now can't hold it.
+    fn clear_notes(&mut self, point: Point, value: Element) {
+        self.notes.clear(point);
+        for peer in self.rules.peers(point) {
+            self.notes.remove(peer, value);
+        }
+        for cage in self.cages.iter().filter(|cage| cage.contains(point)) {
+            for &other in &cage.cells {
+                self.notes.remove(other, value);
+            }
+        }
+    }
+
+    /// Empties a cell without it becoming a move the player can undo.
+    pub fn clear(&mut self, point: Point) {
+        self.current.set(point, None);
+        self.hint = None;
+    }
+
+    /// Fills in up to `count` cells, picked at random from those that are
+    /// empty or wrong and not locked, with their values from the solution.
+    /// Like `clear`, this isn't a move the player can undo. Returns the cells
+    /// filled in.
+    pub fn reveal<F>(&mut self, count: usize, seed: u32, is_locked: F) -> Vec<Point>
+    where
+        F: Fn(Point) -> bool,
+    {
+        let mut points: Vec<Point> = self
+            .points()
+            .into_iter()
+            .filter(|&point| self.is_mutable(point) && !is_locked(point))
+            .filter(|&point| self.current[point] != self.solution[point])
+            .collect();
+        Random::new(u64::from(seed)).shuffle(&mut points);
+        points.truncate(count);
+        for &point in &points {
+            if let Some(value) = self.solution[point] {
+                self.current.set(point, Some(value));
+                self.clear_notes(point, value);
+            }
+        }
+        if !points.is_empty() {
+            self.hint = None;
+        }
+        points
+    }
+
+    /// How many of the row, column and region through `point` are filled in
+    /// correctly.
+    pub fn completed_units(&self, point: Point) -> usize {
+        let Point(x, y) = point;
+        let points = self.points();
+        let regions = self.rules.regions();
+        let units: Vec<Vec<Point>> = vec![
+            points
+                .iter()
+                .cloned()
+                .filter(|&Point(_, other)| other == y)
+                .collect(),
+            points
+                .iter()
+                .cloned()
+                .filter(|&Point(other, _)| other == x)
+                .collect(),
+            regions.region(regions.region_of(point)),
+        ];
+        units
+            .iter()
+            .filter(|unit| {
+                unit.iter()
+                    .all(|&point| self.current[point] == self.solution[point])
+            })
+            .count()
+    }
+
+    /// The cells among `points` the player has filled in wrongly.
+    pub fn wrong_entries(&self, points: &[Point]) -> Vec<Point> {
+        points
+            .iter()
+            .cloned()
+            .filter(|&point| {
+                self.is_mutable(point)
+                    && self.current[point].is_some()
+                    && self.current[point] != self.solution[point]
+            })
+            .collect()
+    }
+
+    /// Notes are only kept for empty cells that the player may fill in.
+    pub fn toggle_note(&mut self, point: Point, value: Element) {
+        if self.is_mutable(point) && self.current[point].is_none() {
+            self.notes.toggle(point, value);
+        }
     }
 
     pub fn remove(&mut self, point: Point) -> Option<Element> {
-        self.moves += 1;
-        let value = self.current[point.into()];
-        self.current = self.current.substitute(point.into(), None);
+        let value = self.current[point];
+        self.set(point, None);
         value
     }
 
+    fn set(&mut self, point: Point, value: Option<Element>) {
+        let before = self.current[point];
+        self.current.set(point, value);
+        self.moves += 1;
+        self.hint = None;
+        if before != value {
+            self.history.record(Move {
+                point,
+                before,
+                after: value,
+            });
+        }
+    }
+
+    /// Reverts the most recent change, returning the cell it touched. A
+    /// change to a cell that can't be edited, such as one `is_locked`, stays
+    /// in the history until it can be.
+    pub fn undo<F>(&mut self, is_locked: F) -> Option<Point>
+    where
+        F: Fn(Point) -> bool,
+    {
+        let mv = self.history.next_undo()?;
+        if !self.can_apply_history(mv.point, is_locked) {
+            return None;
+        }
+        self.history.undo();
+        Some(self.apply_history(mv.point, mv.before))
+    }
+
+    /// Re-applies the most recently undone change, returning the cell it
+    /// touched. Cells that can't be edited are left alone, as in `undo`.
+    pub fn redo<F>(&mut self, is_locked: F) -> Option<Point>
+    where
+        F: Fn(Point) -> bool,
+    {
+        let mv = self.history.next_redo()?;
+        if !self.can_apply_history(mv.point, is_locked) {
+            return None;
+        }
+        self.history.redo();
+        Some(self.apply_history(mv.point, mv.after))
+    }
+
+    fn can_apply_history<F>(&self, point: Point, is_locked: F) -> bool
+    where
+        F: Fn(Point) -> bool,
+    {
+        self.is_mutable(point) && !is_locked(point)
+    }
+
+    fn apply_history(&mut self, point: Point, value: Option<Element>) -> Point {
+        self.current.set(point, value);
+        self.hint = None;
+        point
+    }
+
+    pub fn variant(&self) -> Variant {
+        if !self.cages.is_empty() {
+            Variant::Killer
+        } else if !self.rules.regions().is_square() {
+            Variant::Jigsaw
+        } else {
+            Variant::Classic
+        }
+    }
+
+    /// The puzzle as it was dealt, before the player filled anything in.
+    pub fn problem(&self) -> &Grid {
+        &self.problem
+    }
+
     pub fn is_mutable(&self, point: Point) -> bool {
-        self.problem[point.into()].is_none()
+        self.problem[point].is_none()
+    }
+
+    pub fn order(&self) -> u8 {
+        self.solution.order()
+    }
+
+    /// Whether the rating came from the logical solver. It knows nothing of
+    /// cages, so killer boards are neither rated by it nor given hints.
+    pub fn is_rated(&self) -> bool {
+        self.cages.is_empty()
     }
 
-    pub fn size(&self) -> [u8; sudoku::DIMENSIONS] {
-        let order = self.solution.order() as u8;
-        let dim = order.pow(sudoku::DIMENSIONS as u32);
-        [dim; sudoku::DIMENSIONS]
+    pub fn size(&self) -> [u8; 2] {
+        let dim = self.solution.dimension();
+        [dim; 2]
     }
 
-    pub fn points(&self) -> Vec<KuPoint> {
+    pub fn edges(&self) -> Vec<Edge> {
+        edges::edges(self.rules.regions())
+    }
+
+    pub fn points(&self) -> Vec<Point> {
         self.current.points()
     }
 
@@ -72,6 +558,12 @@ impl Gameboard {
         self.current == self.solution
     }
 
+    /// Whether the player has run out of mistakes.
+    pub fn is_lost(&self) -> bool {
+        self.mistake_limit
+            .map_or(false, |limit| self.mistakes >= limit)
+    }
+
     pub fn move_selected_cell(&mut self, axis: input::Axis, is_positive: bool) {
         let checked_add = |num1, num2, max| {
             if num1 == max - 1 {
@@ -118,3 +610,257 @@ impl Gameboard {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::cages::Cage;
+    use super::super::rules::Parity;
+    use super::*;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+
+    fn board(cages: Vec<Cage>) -> Gameboard {
+        let values: Vec<u8> = PUZZLE.bytes().map(|byte| byte - b'0').collect();
+        let problem = Grid::from_values(3, &values).unwrap();
+        Gameboard::import(problem, None, cages).unwrap()
+    }
+
+    fn save() -> BoardSave {
+        board(vec![]).snapshot()
+    }
+
+    fn assert_corrupt(save: BoardSave) {
+        match Gameboard::restore(save) {
+            Err(SaveError::Corrupt) => {}
+            other => panic!("expected a corrupt save, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn gives_up_importing_after_the_budget() {
+        match Gameboard::import_within(Grid::empty(3), None, vec![], 0) {
+            Err(ImportError::Undecided) => {}
+            other => panic!("expected to give up, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn hints_only_on_boards_without_cages() {
+        let mut classic = board(vec![]);
+        assert!(classic.is_rated());
+        classic.request_hint();
+        assert!(classic.hint.unwrap().is_ok());
+
+        let mut killer = board(vec![Cage {
+            cells: vec![Point(0, 0), Point(1, 0)],
+            sum: 8,
+        }]);
+        assert!(!killer.is_rated());
+        killer.request_hint();
+        match killer.hint {
+            Some(Err(HintError::Cages)) => {}
+            other => panic!("expected no hints for cages, got {:?}", other),
+        }
+        assert_eq!(killer.hints_used, 0);
+    }
+
+    #[test]
+    fn no_hints_until_conflicts_are_cleared() {
+        let mut board = board(vec![]);
+        board.insert(Point(2, 0), Element(5));
+        board.request_hint();
+        match board.hint {
+            Some(Err(HintError::Conflicts)) => {}
+            ref other => panic!("expected no hint over conflicts, got {:?}", other),
+        }
+        assert_eq!(board.hints_used, 0);
+
+        board.remove(Point(2, 0));
+        assert!(board.hint.is_none());
+        board.request_hint();
+        assert!(board.hint.unwrap().is_ok());
+        assert_eq!(board.hints_used, 1);
+    }
+
+    #[test]
+    fn notes_only_go_in_empty_cells() {
+        let mut board = board(vec![]);
+        let (given, empty) = (Point(0, 0), Point(2, 0));
+        board.toggle_note(given, Element(1));
+        assert!(board.notes.values(given).is_empty());
+
+        board.toggle_note(empty, Element(4));
+        board.toggle_note(empty, Element(1));
+        board.toggle_note(empty, Element(4));
+        assert_eq!(board.notes.values(empty), vec![Element(1)]);
+
+        board.insert(empty, Element(4));
+        board.toggle_note(empty, Element(2));
+        assert!(board.notes.values(empty).is_empty());
+    }
+
+    #[test]
+    fn inserting_clears_the_value_from_peer_notes() {
+        let mut board = board(vec![]);
+        let (cell, peer, elsewhere) = (Point(2, 0), Point(3, 0), Point(3, 3));
+        board.toggle_note(peer, Element(4));
+        board.toggle_note(peer, Element(6));
+        board.toggle_note(elsewhere, Element(4));
+        board.insert(cell, Element(4));
+        assert_eq!(board.notes.values(peer), vec![Element(6)]);
+        assert_eq!(board.notes.values(elsewhere), vec![Element(4)]);
+    }
+
+    #[test]
+    fn conflicts_come_from_the_rules_not_the_solution() {
+        let mut board = board(vec![]);
+        let (cell, given) = (Point(2, 0), Point(0, 0));
+        board.insert(cell, Element(1));
+        assert!(board.conflicts().is_empty());
+
+        board.insert(cell, Element(5));
+        let expected: HashSet<Point> = [cell, given].iter().cloned().collect();
+        assert_eq!(board.conflicts(), expected);
+
+        board.remove(cell);
+        assert!(board.conflicts().is_empty());
+    }
+
+    #[test]
+    fn repeats_in_a_cage_conflict() {
+        let (a, b) = (Point(2, 1), Point(3, 0));
+        let mut board = board(vec![Cage {
+            cells: vec![a, b],
+            sum: 8,
+        }]);
+        board.insert(a, Element(2));
+        assert!(board.conflicts().is_empty());
+
+        board.insert(b, Element(2));
+        let expected: HashSet<Point> = [a, b].iter().cloned().collect();
+        assert_eq!(board.conflicts(), expected);
+    }
+
+    #[test]
+    fn scores_a_cell_once() {
+        let mut board = board(vec![]);
+        assert!(board.score(Point(2, 0)));
+        assert!(!board.score(Point(2, 0)));
+        board.restart();
+        assert!(board.score(Point(2, 0)));
+    }
+
+    #[test]
+    fn counts_the_units_a_placement_completes() {
+        let mut board = board(vec![]);
+        let last = Point(2, 0);
+        board.reveal(81, 1, |point| point == last);
+        assert_eq!(board.completed_units(last), 0);
+        assert_eq!(board.completed_units(Point(8, 8)), 3);
+
+        board.insert(last, Element(1));
+        assert_eq!(board.completed_units(last), 0);
+        board.insert(last, Element(4));
+        assert_eq!(board.completed_units(last), 3);
+    }
+
+    #[test]
+    fn reveals_without_a_move_to_undo() {
+        let mut board = board(vec![]);
+        let locked = Point(2, 0);
+        let revealed = board.reveal(81, 1, |point| point == locked);
+        assert!(!revealed.contains(&locked));
+        assert_eq!(board.current[locked], None);
+        assert_eq!(revealed.len(), 50);
+        assert_eq!(board.moves, 0);
+        assert_eq!(board.undo(|_| false), None);
+    }
+
+    #[test]
+    fn undo_leaves_a_locked_cell_alone() {
+        let mut board = board(vec![]);
+        let (first, second) = (Point(2, 0), Point(3, 0));
+        board.insert(first, Element(1));
+        board.insert(second, Element(2));
+        assert_eq!(board.undo(|point| point == second), None);
+        assert_eq!(board.current[second], Some(Element(2)));
+
+        // Once the lock is gone the same move is still there to undo
+        assert_eq!(board.undo(|_| false), Some(second));
+        assert_eq!(board.current[second], None);
+        assert_eq!(board.redo(|point| point == second), None);
+        assert_eq!(board.undo(|_| false), Some(first));
+        assert_eq!(board.redo(|_| false), Some(first));
+        assert_eq!(board.redo(|_| false), Some(second));
+        assert_eq!(board.current[second], Some(Element(2)));
+    }
+
+    #[test]
+    fn undo_leaves_a_given_alone() {
+        let mut board = board(vec![]);
+        let given = Point(0, 0);
+        board.history.record(Move {
+            point: given,
+            before: None,
+            after: Some(Element(5)),
+        });
+        assert_eq!(board.undo(|_| false), None);
+        assert_eq!(board.current[given], Some(Element(5)));
+        assert!(board.history.next_undo().is_some());
+        assert!(board.history.next_redo().is_none());
+    }
+
+    #[test]
+    fn restores_a_snapshot() {
+        let mut save = save();
+        save.selected_cell = Some(Point(8, 8));
+        let board = Gameboard::restore(save).unwrap();
+        assert_eq!(board.selected_cell, Some(Point(8, 8)));
+    }
+
+    #[test]
+    fn rejects_an_order_out_of_range() {
+        let mut save = save();
+        save.order = MAX_ORDER + 1;
+        assert_corrupt(save);
+    }
+
+    #[test]
+    fn rejects_values_too_big_for_the_board() {
+        let mut save = save();
+        save.current[2] = 10;
+        assert_corrupt(save);
+    }
+
+    #[test]
+    fn rejects_notes_for_another_order() {
+        let mut save = save();
+        save.notes = Notes::new(2);
+        assert_corrupt(save);
+    }
+
+    #[test]
+    fn rejects_cells_off_the_board() {
+        let mut selected = save();
+        selected.selected_cell = Some(Point(9, 0));
+        assert_corrupt(selected);
+
+        let mut scored = save();
+        scored.scored.push(Point(0, 9));
+        assert_corrupt(scored);
+
+        let mut caged = save();
+        caged.cages.push(Cage {
+            cells: vec![Point(8, 8), Point(9, 8)],
+            sum: 3,
+        });
+        assert_corrupt(caged);
+
+        let mut shaded = save();
+        shaded
+            .rules
+            .push(Rule::OddEven(vec![(Point(12, 0), Parity::Odd)]));
+        assert_corrupt(shaded);
+    }
+}
@@ -0,0 +1,204 @@
+//! Seeded puzzle generation. The same seed always deals the same puzzle, so
+//! puzzles can be shared and bug reports reproduced.
+
+use super::cages::{self, Cage};
+use super::gameboard::Variant;
+use super::grid::Grid;
+use super::rating::Rating;
+use super::regions::Regions;
+use super::rules::{Rule, RuleSet};
+use super::solver;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many guesses to spend proving a puzzle unique before keeping the
+/// given instead. Only larger boards come anywhere near it.
+const SOLVER_BUDGET: usize = 1_000;
+/// How many guesses to spend filling a grid before starting over.
+const FILL_BUDGET: usize = 100_000;
+/// How many times to start over before dealing without the extra rules.
+const FILL_ATTEMPTS: usize = 10;
+
+/// A SplitMix64 generator. It lives here rather than coming from a crate so
+/// that a seed keeps dealing the same puzzle across platforms and upgrades.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// A seed for a new game, taken from the clock.
+pub fn fresh_seed() -> u32 {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    Random::new(now.as_secs() ^ u64::from(now.subsec_nanos())).next_u64() as u32
+}
+
+/// Puzzles with lots of givens rarely need anything clever, so easier
+/// ratings stop removing them early.
+fn min_givens(rating: Rating, order: u8) -> usize {
+    let cells = (order as usize).pow(4);
+    match rating {
+        Rating::Easy => cells * 4 / 9,
+        Rating::Medium => cells * 10 / 27,
+        _ => 0,
+    }
+}
+
+/// A puzzle as dealt, along with its solution and everything the variant
+/// adds to the rules.
+#[derive(Debug, Clone)]
+pub struct Deal {
+    pub problem: Grid,
+    pub solution: Grid,
+    pub rules: RuleSet,
+    pub cages: Vec<Cage>,
+}
+
+/// Whether a board of `order` can be filled under `rules` at all. Some
+/// combinations, such as diagonals with anti-knight on a 4x4 board, can't.
+/// If that can't be settled quickly, assume it can.
+pub fn can_deal(order: u8, rules: &[Rule]) -> bool {
+    let rules = RuleSet::new(Regions::square(order), rules.to_vec());
+    let solutions = solver::solutions_within(&Grid::empty(order), &rules, &[], 1, FILL_BUDGET);
+    solutions.map_or(true, |solutions| !solutions.is_empty())
+}
+
+/// Deals a puzzle played by `rules` on top of the variant's own, which
+/// `can_deal` should allow. If a grid can't be filled under them after a few
+/// attempts, the puzzle is dealt without them, so check the rules of the deal.
+/// Killer cages usually pin down the solution well enough that few givens,
+/// if any, are left.
+pub fn generate(
+    order: u8,
+    variant: Variant,
+    rules: &[Rule],
+    rating: Rating,
+    random: &mut Random,
+) -> Deal {
+    let mut rules = RuleSet::new(Regions::square(order), rules.to_vec());
+    let filled = (0..FILL_ATTEMPTS)
+        .filter_map(|_| solver::random_solution(&rules, random, FILL_BUDGET))
+        .next();
+    let solution = match filled {
+        Some(solution) => solution,
+        None => {
+            rules = RuleSet::classic(order);
+            loop {
+                if let Some(solution) = solver::random_solution(&rules, random, FILL_BUDGET) {
+                    break solution;
+                }
+            }
+        }
+    };
+    if variant == Variant::Jigsaw {
+        rules.set_regions(Regions::random(&solution, random));
+    }
+    rules.shade(&solution, random);
+    let (cages, min_givens) = match variant {
+        Variant::Killer => (cages::random_cages(&solution, random), 0),
+        _ => (vec![], min_givens(rating, order)),
+    };
+    let problem = remove_givens(&solution, &rules, &cages, min_givens, random);
+    Deal {
+        problem,
+        solution,
+        rules,
+        cages,
+    }
+}
+
+/// Takes givens away in a random order for as long as the puzzle can be
+/// shown to keep exactly one solution.
+fn remove_givens(
+    solution: &Grid,
+    rules: &RuleSet,
+    cages: &[Cage],
+    min_givens: usize,
+    random: &mut Random,
+) -> Grid {
+    let mut problem = solution.clone();
+    let mut points = problem.points();
+    random.shuffle(&mut points);
+
+    let mut givens = points.len();
+    for point in points {
+        if givens <= min_givens {
+            break;
+        }
+        let value = problem[point];
+        problem.set(point, None);
+        match solver::solutions_within(&problem, rules, cages, 2, SOLVER_BUDGET) {
+            Some(ref solutions) if solutions.len() == 1 => givens -= 1,
+            _ => problem.set(point, value),
+        }
+    }
+    problem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deals_without_rules_no_grid_can_follow() {
+        let rules = [Rule::Diagonals, Rule::AntiKnight];
+        assert!(!can_deal(2, &rules));
+        let mut random = Random::new(1);
+        let deal = generate(2, Variant::Classic, &rules, Rating::Easy, &mut random);
+        assert!(deal.rules.rules().is_empty());
+        assert_eq!(deal.solution.order(), 2);
+    }
+
+    #[test]
+    fn gives_up_filling_after_the_budget() {
+        let rules = RuleSet::classic(3);
+        let mut random = Random::new(1);
+        assert!(solver::random_solution(&rules, &mut random, 0).is_none());
+        assert!(solver::solutions_within(&Grid::empty(3), &rules, &[], 1, 0).is_none());
+        assert!(solver::random_solution(&rules, &mut random, FILL_BUDGET).is_some());
+    }
+
+    #[test]
+    fn keeps_givens_that_are_needed_for_one_solution() {
+        let rules = RuleSet::classic(2);
+        let mut random = Random::new(3);
+        let solution = solver::random_solution(&rules, &mut random, FILL_BUDGET).unwrap();
+        let problem = remove_givens(&solution, &rules, &[], 0, &mut random);
+        let solutions = solver::solutions_within(&problem, &rules, &[], 2, FILL_BUDGET).unwrap();
+        assert_eq!(solutions, vec![solution.clone()]);
+
+        let min_givens = 12;
+        let problem = remove_givens(&solution, &rules, &[], min_givens, &mut random);
+        let givens = problem
+            .points()
+            .into_iter()
+            .filter(|&point| problem[point].is_some());
+        assert_eq!(givens.count(), min_givens);
+    }
+}
@@ -0,0 +1,93 @@
+use super::gameboard::Point;
+use std::char;
+use std::ops::Index;
+use sudoku::Element;
+
+/// The values on a board, stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    order: u8,
+    cells: Vec<Option<Element>>,
+}
+
+impl Grid {
+    pub fn empty(order: u8) -> Self {
+        let dimension = order as usize * order as usize;
+        Grid {
+            order,
+            cells: vec![None; dimension * dimension],
+        }
+    }
+
+    /// Builds a grid from one byte per cell, where `0` is an empty cell.
+    /// Returns `None` if there are too many or too few values for the order.
+    pub fn from_values(order: u8, values: &[u8]) -> Option<Self> {
+        let mut grid = Grid::empty(order);
+        if values.len() != grid.cells.len() {
+            return None;
+        }
+        for (cell, &value) in grid.cells.iter_mut().zip(values) {
+            *cell = if value == 0 {
+                None
+            } else {
+                Some(Element(value))
+            };
+        }
+        Some(grid)
+    }
+
+    /// The inverse of `from_values`.
+    pub fn values(&self) -> Vec<u8> {
+        self.cells
+            .iter()
+            .map(|cell| cell.map_or(0, |Element(value)| value))
+            .collect()
+    }
+
+    pub fn order(&self) -> u8 {
+        self.order
+    }
+
+    pub fn dimension(&self) -> u8 {
+        self.order * self.order
+    }
+
+    fn offset(&self, point: Point) -> usize {
+        let Point(x, y) = point;
+        y as usize * self.dimension() as usize + x as usize
+    }
+
+    pub fn set(&mut self, point: Point, value: Option<Element>) {
+        let offset = self.offset(point);
+        self.cells[offset] = value;
+    }
+
+    /// Every point on the grid, row by row.
+    pub fn points(&self) -> Vec<Point> {
+        let dimension = self.dimension();
+        (0..dimension)
+            .flat_map(|y| (0..dimension).map(move |x| Point(x, y)))
+            .collect()
+    }
+}
+
+impl Index<Point> for Grid {
+    type Output = Option<Element>;
+
+    fn index(&self, point: Point) -> &Option<Element> {
+        &self.cells[self.offset(point)]
+    }
+}
+
+/// How a value is written. Values above nine carry on through the alphabet,
+/// so larger boards still get one character per cell.
+pub fn glyph(value: u8) -> char {
+    char::from_digit(u32::from(value), 36)
+        .unwrap_or('?')
+        .to_ascii_uppercase()
+}
+
+/// The inverse of `glyph`. Letters may be either case.
+pub fn from_glyph(glyph: char) -> Option<u8> {
+    glyph.to_digit(36).map(|value| value as u8)
+}
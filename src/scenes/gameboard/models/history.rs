@@ -0,0 +1,52 @@
+use super::gameboard::Point;
+use sudoku::Element;
+
+/// A single change to a cell on the board, with enough information to
+/// play it backwards or forwards again.
+#[derive(Debug, Copy, Clone)]
+pub struct Move {
+    pub point: Point,
+    pub before: Option<Element>,
+    pub after: Option<Element>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    undo_stack: Vec<Move>,
+    redo_stack: Vec<Move>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Records a new move. Anything that was previously undone can no longer
+    /// be redone once the player makes a fresh change.
+    pub fn record(&mut self, mv: Move) {
+        self.undo_stack.push(mv);
+        self.redo_stack.clear();
+    }
+
+    /// The move `undo` would take back, left where it is.
+    pub fn next_undo(&self) -> Option<Move> {
+        self.undo_stack.last().cloned()
+    }
+
+    /// The move `redo` would play again, left where it is.
+    pub fn next_redo(&self) -> Option<Move> {
+        self.redo_stack.last().cloned()
+    }
+
+    pub fn undo(&mut self) -> Option<Move> {
+        let mv = self.undo_stack.pop()?;
+        self.redo_stack.push(mv);
+        Some(mv)
+    }
+
+    pub fn redo(&mut self) -> Option<Move> {
+        let mv = self.redo_stack.pop()?;
+        self.undo_stack.push(mv);
+        Some(mv)
+    }
+}
@@ -0,0 +1,516 @@
+//! Reading puzzles from the text formats used by books and websites:
+//!
+//! * a single line with one character per cell, `.` or `0` for blank cells
+//!   and letters for values above nine,
+//! * `.sdk` files, which hold one puzzle laid out over several lines,
+//! * `.sdm` files, which hold many puzzles, one line each,
+//! * `.killer` files, which hold one killer puzzle and its cages,
+//! * `.jigsaw` files, which hold one puzzle and the shape of its regions.
+//!
+//! The size of the board is worked out from the number of cells.
+
+use super::cages::Cage;
+use super::gameboard::{Gameboard, Point, MAX_ORDER, MIN_ORDER};
+use super::grid::{self, Grid};
+use super::regions::Regions;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Fail)]
+pub enum ImportError {
+    #[fail(display = "could not read puzzle file: {}", _0)]
+    Io(#[cause] io::Error),
+    #[fail(display = "{} cells is not the size of any board we support", found)]
+    WrongLength { found: usize },
+    #[fail(display = "unexpected character {:?} at cell {}", character, position)]
+    InvalidCharacter { character: char, position: usize },
+    #[fail(display = "line {}: {}", line, error)]
+    Line {
+        line: usize,
+        error: Box<ImportError>,
+    },
+    #[fail(display = "no puzzles found")]
+    Empty,
+    #[fail(display = "expected a line naming each cell's cage")]
+    MissingCageMap,
+    #[fail(display = "expected a line naming each cell's region")]
+    MissingRegionMap,
+    #[fail(display = "the regions must be connected and hold one cell per value")]
+    InvalidRegions,
+    #[fail(display = "expected a cage name followed by its sum")]
+    InvalidCage,
+    #[fail(display = "cage {:?} has no sum", name)]
+    MissingSum { name: char },
+    #[fail(display = "cage {:?} has a sum but no cells", name)]
+    UnusedSum { name: char },
+    #[fail(
+        display = "cage {:?} can't add up to {} with {} cells",
+        name, sum, size
+    )]
+    ImpossibleCage { name: char, sum: u32, size: usize },
+    #[fail(
+        display = "puzzle {} was requested but there are only {}",
+        index, count
+    )]
+    NoSuchPuzzle { index: usize, count: usize },
+    #[fail(display = "the puzzle has no solution")]
+    NoSolution,
+    #[fail(display = "the puzzle has more than one solution")]
+    MultipleSolutions,
+    #[fail(display = "gave up checking that the puzzle has only one solution")]
+    Undecided,
+}
+
+impl From<io::Error> for ImportError {
+    fn from(err: io::Error) -> Self {
+        ImportError::Io(err)
+    }
+}
+
+fn in_line(line: usize, error: ImportError) -> ImportError {
+    ImportError::Line {
+        line,
+        error: Box::new(error),
+    }
+}
+
+/// A puzzle as read from a file, before it has been checked for a unique
+/// solution.
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub givens: Grid,
+    /// Jigsaw regions, or `None` for square sections.
+    pub regions: Option<Regions>,
+    pub cages: Vec<Cage>,
+}
+
+impl From<Grid> for Puzzle {
+    fn from(givens: Grid) -> Self {
+        Puzzle {
+            givens,
+            regions: None,
+            cages: vec![],
+        }
+    }
+}
+
+/// Parses a puzzle written as one character per cell. Whitespace is ignored.
+pub fn parse_line(line: &str) -> Result<Grid, ImportError> {
+    let cells: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+    let order = (MIN_ORDER..=MAX_ORDER)
+        .find(|&order| (order as usize).pow(4) == cells.len())
+        .ok_or(ImportError::WrongLength { found: cells.len() })?;
+    let dimension = order * order;
+    let mut values = Vec::with_capacity(cells.len());
+    for (position, &character) in cells.iter().enumerate() {
+        let value = match character {
+            '.' => Some(0),
+            _ => grid::from_glyph(character).filter(|&value| value <= dimension),
+        };
+        match value {
+            Some(value) => values.push(value),
+            None => {
+                return Err(ImportError::InvalidCharacter {
+                    character,
+                    position: position + 1,
+                })
+            }
+        }
+    }
+    Ok(Grid::from_values(order, &values).unwrap())
+}
+
+/// Parses a `.sdk` file. Lines starting with `#` hold metadata such as the
+/// author and are skipped. Some files go on to save the player's progress in
+/// a `[State]` section; only the `[Puzzle]` section, or the lines before any
+/// section header, are read.
+pub fn parse_sdk(contents: &str) -> Result<Grid, ImportError> {
+    let mut grid = String::new();
+    let mut in_puzzle = true;
+    for line in contents.lines().map(|line| line.trim()) {
+        if line.starts_with('[') {
+            in_puzzle = line.eq_ignore_ascii_case("[puzzle]");
+        } else if in_puzzle && !line.starts_with('#') {
+            grid.push_str(line);
+        }
+    }
+    parse_line(&grid)
+}
+
+/// Parses a `.sdm` file, or any other file with one puzzle per line.
+pub fn parse_sdm(contents: &str) -> Result<Vec<Grid>, ImportError> {
+    let mut puzzles = vec![];
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let grid = parse_line(line).map_err(|error| in_line(i + 1, error))?;
+        puzzles.push(grid);
+    }
+    if puzzles.is_empty() {
+        return Err(ImportError::Empty);
+    }
+    Ok(puzzles)
+}
+
+/// Parses a `.killer` file. After the givens, written as in `parse_line`,
+/// comes a line naming each cell's cage with one character per cell (`.` for
+/// cells outside any cage), then one line per cage with its name and sum,
+/// such as `a 12`. Blank lines and lines starting with `#` are skipped.
+pub fn parse_killer(contents: &str) -> Result<Puzzle, ImportError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|&(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (line, givens) = lines.next().ok_or(ImportError::Empty)?;
+    let givens = parse_line(givens).map_err(|error| in_line(line, error))?;
+
+    let (line, map) = lines.next().ok_or(ImportError::MissingCageMap)?;
+    let map: Vec<char> = map.chars().filter(|c| !c.is_whitespace()).collect();
+    let points = givens.points();
+    if map.len() != points.len() {
+        return Err(in_line(line, ImportError::WrongLength { found: map.len() }));
+    }
+
+    let mut sums = HashMap::new();
+    for (line, text) in lines {
+        let parts: Vec<&str> = text.split_whitespace().collect();
+        let mut name = parts[0].chars();
+        match (
+            name.next(),
+            name.next(),
+            parts.get(1).map(|sum| sum.parse::<u32>()),
+        ) {
+            (Some(name), None, Some(Ok(sum))) if parts.len() == 2 => {
+                sums.insert(name, sum);
+            }
+            _ => return Err(in_line(line, ImportError::InvalidCage)),
+        }
+    }
+
+    // Cages are kept in the order their first cell appears on the board
+    let mut names: Vec<char> = vec![];
+    let mut cells: HashMap<char, Vec<Point>> = HashMap::new();
+    for (&name, &point) in map.iter().zip(&points) {
+        if name == '.' {
+            continue;
+        }
+        if !cells.contains_key(&name) {
+            names.push(name);
+        }
+        cells.entry(name).or_insert_with(Vec::new).push(point);
+    }
+    let mut cages = Vec::with_capacity(names.len());
+    for name in names {
+        let sum = sums.remove(&name).ok_or(ImportError::MissingSum { name })?;
+        let cells = cells.remove(&name).unwrap();
+        let cage = Cage { cells, sum };
+        if !cage.is_possible(givens.dimension()) {
+            let size = cage.cells.len();
+            return Err(ImportError::ImpossibleCage { name, sum, size });
+        }
+        cages.push(cage);
+    }
+    if let Some(&name) = sums.keys().next() {
+        return Err(ImportError::UnusedSum { name });
+    }
+    Ok(Puzzle {
+        givens,
+        regions: None,
+        cages,
+    })
+}
+
+/// Parses a `.jigsaw` file: the givens, written as in `parse_line`, then a
+/// line naming each cell's region with one character per cell. Blank lines
+/// and lines starting with `#` are skipped.
+pub fn parse_jigsaw(contents: &str) -> Result<Puzzle, ImportError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|&(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+    let (line, givens) = lines.next().ok_or(ImportError::Empty)?;
+    let givens = parse_line(givens).map_err(|error| in_line(line, error))?;
+
+    let (line, map) = lines.next().ok_or(ImportError::MissingRegionMap)?;
+    let map: Vec<char> = map.chars().filter(|c| !c.is_whitespace()).collect();
+    if map.len() != givens.points().len() {
+        return Err(in_line(line, ImportError::WrongLength { found: map.len() }));
+    }
+
+    // Regions are numbered in the order their first cell appears
+    let mut names: Vec<char> = vec![];
+    let mut cells = Vec::with_capacity(map.len());
+    for &name in &map {
+        let region = match names.iter().position(|&other| other == name) {
+            Some(region) => region,
+            None => {
+                names.push(name);
+                names.len() - 1
+            }
+        };
+        cells.push(region as u8);
+    }
+    if names.len() != givens.dimension() as usize {
+        return Err(ImportError::InvalidRegions);
+    }
+    let regions = Regions::from_cells(givens.order(), cells).ok_or(ImportError::InvalidRegions)?;
+    Ok(Puzzle {
+        givens,
+        regions: Some(regions),
+        cages: vec![],
+    })
+}
+
+/// Reads every puzzle in the file at `path`, choosing the format by the
+/// file's extension.
+pub fn parse_file(path: &Path) -> Result<Vec<Puzzle>, ImportError> {
+    let contents = fs::read_to_string(path)?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("sdk") => parse_sdk(&contents).map(|grid| vec![grid.into()]),
+        Some("killer") => parse_killer(&contents).map(|puzzle| vec![puzzle]),
+        Some("jigsaw") => parse_jigsaw(&contents).map(|puzzle| vec![puzzle]),
+        _ => parse_sdm(&contents).map(|grids| grids.into_iter().map(Puzzle::from).collect()),
+    }
+}
+
+/// Builds a board from the `index`th puzzle (counting from zero) in the file
+/// at `path`.
+pub fn load(path: &Path, index: usize) -> Result<Gameboard, ImportError> {
+    let mut puzzles = parse_file(path)?;
+    if index >= puzzles.len() {
+        return Err(ImportError::NoSuchPuzzle {
+            index: index + 1,
+            count: puzzles.len(),
+        });
+    }
+    let puzzle = puzzles.swap_remove(index);
+    Gameboard::import(puzzle.givens, puzzle.regions, puzzle.cages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    const PUZZLE: &str = "12.4..12.143432.";
+    const SOLVED: &str = "1234341221434321";
+
+    /// Writes `contents` to a file named `name` in the temporary directory.
+    fn write_temp(name: &str, contents: &str) -> ::std::path::PathBuf {
+        let path = env::temp_dir().join(format!("import-test-{}", name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn parses_a_line() {
+        let grid = parse_line("1234 3412 2143 4321").unwrap();
+        assert_eq!(grid.order(), 2);
+        assert_eq!(grid[Point(1, 2)], Some(::sudoku::Element(1)));
+
+        let grid = parse_line(PUZZLE).unwrap();
+        assert_eq!(grid[Point(2, 0)], None);
+    }
+
+    #[test]
+    fn rejects_a_line_of_the_wrong_length() {
+        match parse_line("1234") {
+            Err(ImportError::WrongLength { found: 4 }) => {}
+            other => panic!("expected the wrong length, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_values_too_big_for_the_board() {
+        match parse_line("12.5..12.143432.") {
+            Err(ImportError::InvalidCharacter {
+                character: '5',
+                position: 4,
+            }) => {}
+            other => panic!("expected an invalid character, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reads_only_the_puzzle_section_of_an_sdk_file() {
+        let contents = "#A Someone\n[Puzzle]\n12.4\n..12\n.143\n432.\n[State]\n1234\n3412\n";
+        let grid = parse_sdk(contents).unwrap();
+        assert_eq!(grid, parse_line(PUZZLE).unwrap());
+
+        let grid = parse_sdk("12.4\n..12\n.143\n432.\n").unwrap();
+        assert_eq!(grid, parse_line(PUZZLE).unwrap());
+    }
+
+    #[test]
+    fn parses_an_sdm_file() {
+        let contents = format!("# two puzzles\n{}\n\n{}\n", PUZZLE, SOLVED);
+        assert_eq!(parse_sdm(&contents).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reports_the_line_of_a_bad_sdm_puzzle() {
+        let contents = format!("{}\n12x4..12.143432.\n", PUZZLE);
+        match parse_sdm(&contents) {
+            Err(ImportError::Line { line: 2, error }) => match *error {
+                ImportError::InvalidCharacter { character: 'x', .. } => {}
+                other => panic!("expected an invalid character, got {:?}", other),
+            },
+            other => panic!("expected an error on line 2, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_an_sdm_file_without_puzzles() {
+        match parse_sdm("# nothing here\n\n") {
+            Err(ImportError::Empty) => {}
+            other => panic!("expected no puzzles, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_killer_file() {
+        let contents = "................\naabb aabb ccdd ccdd\na 10\nb 10\nc 10\nd 10\n";
+        let puzzle = parse_killer(contents).unwrap();
+        assert_eq!(puzzle.cages.len(), 4);
+        assert_eq!(
+            puzzle.cages[1].cells,
+            vec![Point(2, 0), Point(3, 0), Point(2, 1), Point(3, 1)]
+        );
+        assert_eq!(puzzle.cages[1].sum, 10);
+    }
+
+    #[test]
+    fn rejects_bad_killer_files() {
+        match parse_killer("# nothing here\n") {
+            Err(ImportError::Empty) => {}
+            other => panic!("expected no puzzle, got {:?}", other),
+        }
+        match parse_killer("................\n") {
+            Err(ImportError::MissingCageMap) => {}
+            other => panic!("expected a missing cage map, got {:?}", other),
+        }
+        match parse_killer("................\naabb\n") {
+            Err(ImportError::Line { line: 2, error }) => match *error {
+                ImportError::WrongLength { found: 4 } => {}
+                other => panic!("expected the wrong length, got {:?}", other),
+            },
+            other => panic!("expected an error on line 2, got {:?}", other),
+        }
+        match parse_killer("................\naa..............\nab 3\n") {
+            Err(ImportError::Line { line: 3, error }) => match *error {
+                ImportError::InvalidCage => {}
+                other => panic!("expected an invalid cage, got {:?}", other),
+            },
+            other => panic!("expected an error on line 3, got {:?}", other),
+        }
+        match parse_killer("................\naabb............\na 3\n") {
+            Err(ImportError::MissingSum { name: 'b' }) => {}
+            other => panic!("expected a missing sum, got {:?}", other),
+        }
+        match parse_killer("................\naa..............\na 3\nb 7\n") {
+            Err(ImportError::UnusedSum { name: 'b' }) => {}
+            other => panic!("expected an unused sum, got {:?}", other),
+        }
+        match parse_killer("................\naaaaa...........\na 14\n") {
+            Err(ImportError::ImpossibleCage {
+                name: 'a',
+                sum: 14,
+                size: 5,
+            }) => {}
+            other => panic!("expected an impossible cage, got {:?}", other),
+        }
+        match parse_killer("................\naa..............\na 8\n") {
+            Err(ImportError::ImpossibleCage { name: 'a', .. }) => {}
+            other => panic!("expected an impossible cage, got {:?}", other),
+        }
+        match parse_killer("................\naa..............\na 2\n") {
+            Err(ImportError::ImpossibleCage { name: 'a', .. }) => {}
+            other => panic!("expected an impossible cage, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_a_jigsaw_file() {
+        let contents = "# regions\n................\naabb acbb acdd ccdd\n";
+        let puzzle = parse_jigsaw(contents).unwrap();
+        let regions = puzzle.regions.unwrap();
+        assert_eq!(
+            regions.region(2),
+            vec![Point(1, 1), Point(1, 2), Point(0, 3), Point(1, 3)]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_jigsaw_files() {
+        match parse_jigsaw("................\n") {
+            Err(ImportError::MissingRegionMap) => {}
+            other => panic!("expected a missing region map, got {:?}", other),
+        }
+        match parse_jigsaw("................\naabb\n") {
+            Err(ImportError::Line { line: 2, error }) => match *error {
+                ImportError::WrongLength { found: 4 } => {}
+                other => panic!("expected the wrong length, got {:?}", other),
+            },
+            other => panic!("expected an error on line 2, got {:?}", other),
+        }
+        // Region a is split in two, and region e has too few cells
+        for map in &["abba abbb ccdd ccdd", "aabb aabb ccdd cdde"] {
+            match parse_jigsaw(&format!("................\n{}\n", map)) {
+                Err(ImportError::InvalidRegions) => {}
+                other => panic!("expected invalid regions, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn loads_a_puzzle_with_its_regions() {
+        // Only unique with these regions; square sections allow six solutions
+        let path = write_temp("regions.jigsaw", "......1......341\naabb acbb acdd ccdd\n");
+        let board = load(&path, 0).unwrap();
+        assert_eq!(board.rules.regions().region(2)[0], Point(1, 1));
+        match load(&write_temp("regions.sdm", "......1......341\n"), 0) {
+            Err(ImportError::MultipleSolutions) => {}
+            other => panic!("expected several solutions, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn reports_a_missing_file() {
+        match parse_file(Path::new("/nonexistent/puzzle.sdm")) {
+            Err(ImportError::Io(_)) => {}
+            other => panic!("expected an io error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn loads_a_puzzle_by_index() {
+        let path = write_temp("index.sdm", &format!("{}\n{}\n", PUZZLE, SOLVED));
+        assert!(load(&path, 1).is_ok());
+        match load(&path, 2) {
+            Err(ImportError::NoSuchPuzzle { index: 3, count: 2 }) => {}
+            other => panic!("expected no such puzzle, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn checks_the_puzzle_has_one_solution() {
+        let path = write_temp("broken.sdk", "11..\n....\n....\n....\n");
+        match load(&path, 0) {
+            Err(ImportError::NoSolution) => {}
+            other => panic!("expected no solution, got {:?}", other.map(|_| ())),
+        }
+        let path = write_temp("empty.sdk", "....\n....\n....\n....\n");
+        match load(&path, 0) {
+            Err(ImportError::MultipleSolutions) => {}
+            other => panic!("expected several solutions, got {:?}", other.map(|_| ())),
+        }
+    }
+}
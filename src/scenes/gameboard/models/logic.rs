@@ -0,0 +1,876 @@
+//! A solver that works the way a person would. It keeps a set of candidates
+//! for every empty cell and only ever makes progress through a named
+//! technique, so every step it takes can be explained to the player.
+
+use super::gameboard::Point;
+use super::grid::Grid;
+use super::rules::RuleSet;
+use super::units;
+use sudoku::Element;
+
+/// Techniques in the order the solver tries them, which is also roughly the
+/// order of how hard they are for a person to spot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    NakedSingle,
+    HiddenSingle,
+    NakedPair,
+    HiddenPair,
+    NakedTriple,
+    HiddenTriple,
+    PointingPair,
+    BoxLineReduction,
+    XWing,
+}
+
+impl Technique {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "Naked Single",
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::NakedPair => "Naked Pair",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::PointingPair => "Pointing Pair",
+            Technique::BoxLineReduction => "Box/Line Reduction",
+            Technique::XWing => "X-Wing",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Step {
+    Place(Point, Element),
+    Eliminate(Vec<(Point, Element)>),
+}
+
+/// A single application of a technique: the cells that form the pattern,
+/// and what the pattern lets us conclude.
+#[derive(Debug, Clone)]
+pub struct Deduction {
+    pub technique: Technique,
+    pub cells: Vec<Point>,
+    pub step: Step,
+    reason: String,
+}
+
+impl Deduction {
+    pub fn explanation(&self) -> String {
+        format!("{}: {}", self.technique.name(), self.reason)
+    }
+
+    pub fn placement(&self) -> Option<(Point, Element)> {
+        match self.step {
+            Step::Place(point, value) => Some((point, value)),
+            Step::Eliminate(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Fail)]
+pub enum HintError {
+    #[fail(display = "Fix the highlighted conflicts first.")]
+    Conflicts,
+    #[fail(display = "Some entries are wrong. Fix them first.")]
+    Mistakes,
+    #[fail(display = "The board is already complete.")]
+    Solved,
+    #[fail(display = "No logical step found. Time to guess!")]
+    Stuck,
+    #[fail(display = "Hints don't know about cages yet.")]
+    Cages,
+}
+
+/// The next value the player can place, along with any eliminations that
+/// had to be made before it could be found.
+#[derive(Debug, Clone)]
+pub struct Hint {
+    pub deductions: Vec<Deduction>,
+}
+
+impl Hint {
+    pub fn placement(&self) -> Option<(Point, Element)> {
+        self.deductions.last().and_then(|d| d.placement())
+    }
+
+    /// Every cell involved in the hint, including the pattern cells of the
+    /// eliminations leading up to it.
+    pub fn cells(&self) -> Vec<Point> {
+        let mut cells: Vec<Point> = self
+            .deductions
+            .iter()
+            .flat_map(|d| d.cells.iter().cloned())
+            .collect();
+        cells.sort_by_key(|&Point(x, y)| (y, x));
+        cells.dedup();
+        cells
+    }
+
+    pub fn explanation(&self) -> String {
+        let (last, earlier) = match self.deductions.split_last() {
+            Some(split) => split,
+            None => return String::new(),
+        };
+        if earlier.is_empty() {
+            last.explanation()
+        } else {
+            let mut techniques: Vec<&str> = earlier.iter().map(|d| d.technique.name()).collect();
+            techniques.dedup();
+            format!(
+                "{} (after eliminating candidates with {})",
+                last.explanation(),
+                techniques.join(", ")
+            )
+        }
+    }
+}
+
+/// Finds the next value that can be placed on `grid` using only logic. A
+/// wrong entry would lead to deductions that don't hold, so `grid` is checked
+/// against `solution` first.
+pub fn find_hint(grid: &Grid, solution: &Grid, rules: &RuleSet) -> Result<Hint, HintError> {
+    let is_wrong = grid
+        .points()
+        .into_iter()
+        .any(|point| grid[point].is_some() && grid[point] != solution[point]);
+    if is_wrong {
+        return Err(HintError::Mistakes);
+    }
+    let mut solver = LogicalSolver::new(grid, rules);
+    if solver.is_solved() {
+        return Err(HintError::Solved);
+    }
+    let mut deductions = vec![];
+    while let Some(deduction) = solver.step() {
+        let placed = deduction.placement().is_some();
+        deductions.push(deduction);
+        if placed {
+            return Ok(Hint { deductions });
+        }
+    }
+    Err(HintError::Stuck)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum UnitKind {
+    Row,
+    Column,
+    Section,
+    Diagonal,
+}
+
+#[derive(Debug, Clone)]
+struct Unit {
+    kind: UnitKind,
+    index: u8,
+    cells: Vec<Point>,
+}
+
+impl Unit {
+    fn name(&self) -> String {
+        let kind = match self.kind {
+            UnitKind::Row => "row",
+            UnitKind::Column => "column",
+            UnitKind::Section => "box",
+            UnitKind::Diagonal => "diagonal",
+        };
+        format!("{} {}", kind, self.index + 1)
+    }
+}
+
+fn cell_name(point: Point) -> String {
+    let Point(x, y) = point;
+    format!("r{}c{}", y + 1, x + 1)
+}
+
+fn cell_names(points: &[Point]) -> String {
+    points
+        .iter()
+        .map(|&point| cell_name(point))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn value_names(values: &[u8]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn bits(mask: u32) -> Vec<u8> {
+    (0..32).filter(|&bit| mask & (1 << bit) != 0).collect()
+}
+
+/// All the ways of picking `k` items out of `items`, preserving order.
+fn combinations<T: Copy>(items: &[T], k: usize) -> Vec<Vec<T>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let mut result = vec![];
+    for (i, &item) in items.iter().enumerate() {
+        for mut rest in combinations(&items[i + 1..], k - 1) {
+            rest.insert(0, item);
+            result.push(rest);
+        }
+    }
+    result
+}
+
+#[derive(Debug, Clone)]
+pub struct LogicalSolver {
+    order: u8,
+    rules: RuleSet,
+    values: Vec<Option<u8>>,
+    candidates: Vec<u32>,
+    units: Vec<Unit>,
+}
+
+impl LogicalSolver {
+    pub fn new(grid: &Grid, rules: &RuleSet) -> Self {
+        let values = grid
+            .points()
+            .into_iter()
+            .map(|point| grid[point].map(|Element(value)| value))
+            .collect();
+        Self::from_values(rules.clone(), values)
+    }
+
+    fn from_values(rules: RuleSet, values: Vec<Option<u8>>) -> Self {
+        let order = rules.order();
+        let dimension = order * order;
+        let units = (0..dimension)
+            .map(|y| Unit {
+                kind: UnitKind::Row,
+                index: y,
+                cells: units::row(y, order),
+            })
+            .chain((0..dimension).map(|x| Unit {
+                kind: UnitKind::Column,
+                index: x,
+                cells: units::column(x, order),
+            }))
+            .chain(
+                rules
+                    .regions()
+                    .all()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, cells)| Unit {
+                        kind: UnitKind::Section,
+                        index: i as u8,
+                        cells,
+                    }),
+            )
+            .chain(
+                rules
+                    .extra_units()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, cells)| Unit {
+                        kind: UnitKind::Diagonal,
+                        index: i as u8,
+                        cells,
+                    }),
+            )
+            .collect();
+
+        let mut candidates = vec![];
+        for y in 0..dimension {
+            for x in 0..dimension {
+                let allowed = (1..=dimension)
+                    .filter(|&value| rules.allows(Point(x, y), value))
+                    .fold(0, |mask, value| mask | 1 << value);
+                candidates.push(allowed);
+            }
+        }
+        let mut solver = LogicalSolver {
+            order,
+            rules,
+            values,
+            candidates,
+            units,
+        };
+        for y in 0..dimension {
+            for x in 0..dimension {
+                let point = Point(x, y);
+                if let Some(value) = solver.value(point) {
+                    solver.place(point, value);
+                }
+            }
+        }
+        solver
+    }
+
+    fn dimension(&self) -> u8 {
+        self.order * self.order
+    }
+
+    fn index(&self, point: Point) -> usize {
+        let Point(x, y) = point;
+        y as usize * self.dimension() as usize + x as usize
+    }
+
+    fn value(&self, point: Point) -> Option<u8> {
+        self.values[self.index(point)]
+    }
+
+    fn candidates(&self, point: Point) -> u32 {
+        self.candidates[self.index(point)]
+    }
+
+    fn has_candidate(&self, point: Point, value: u8) -> bool {
+        self.candidates(point) & (1 << value) != 0
+    }
+
+    fn place(&mut self, point: Point, value: u8) {
+        let index = self.index(point);
+        self.values[index] = Some(value);
+        self.candidates[index] = 0;
+        for peer in self.rules.peers(point) {
+            let index = self.index(peer);
+            self.candidates[index] &= !(1 << value);
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.values.iter().all(|value| value.is_some())
+    }
+
+    /// Finds and applies the simplest deduction available.
+    pub fn step(&mut self) -> Option<Deduction> {
+        let deduction = self.next_deduction()?;
+        match deduction.step {
+            Step::Place(point, Element(value)) => self.place(point, value),
+            Step::Eliminate(ref eliminations) => {
+                for &(point, Element(value)) in eliminations {
+                    let index = self.index(point);
+                    self.candidates[index] &= !(1 << value);
+                }
+            }
+        }
+        Some(deduction)
+    }
+
+    pub fn next_deduction(&self) -> Option<Deduction> {
+        self.naked_single()
+            .or_else(|| self.hidden_single())
+            .or_else(|| self.naked_subset(2))
+            .or_else(|| self.hidden_subset(2))
+            .or_else(|| self.naked_subset(3))
+            .or_else(|| self.hidden_subset(3))
+            .or_else(|| self.pointing_pair())
+            .or_else(|| self.box_line_reduction())
+            .or_else(|| self.x_wing())
+    }
+
+    fn empty_cells<'a>(&'a self, cells: &'a [Point]) -> impl Iterator<Item = Point> + 'a {
+        cells
+            .iter()
+            .cloned()
+            .filter(move |&point| self.value(point).is_none())
+    }
+
+    fn eliminations(&self, cells: &[Point], values: &[u8]) -> Vec<(Point, Element)> {
+        let mut eliminations = vec![];
+        for &point in cells {
+            for &value in values {
+                if self.has_candidate(point, value) {
+                    eliminations.push((point, Element(value)));
+                }
+            }
+        }
+        eliminations
+    }
+
+    fn naked_single(&self) -> Option<Deduction> {
+        let dimension = self.dimension();
+        for y in 0..dimension {
+            for x in 0..dimension {
+                let point = Point(x, y);
+                if self.value(point).is_some() {
+                    continue;
+                }
+                let candidates = bits(self.candidates(point));
+                if candidates.len() == 1 {
+                    let value = candidates[0];
+                    return Some(Deduction {
+                        technique: Technique::NakedSingle,
+                        cells: vec![point],
+                        step: Step::Place(point, Element(value)),
+                        reason: format!(
+                            "{} is the only value left for {}.",
+                            value,
+                            cell_name(point)
+                        ),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn hidden_single(&self) -> Option<Deduction> {
+        for unit in &self.units {
+            for value in 1..=self.dimension() {
+                let places: Vec<Point> = self
+                    .empty_cells(&unit.cells)
+                    .filter(|&point| self.has_candidate(point, value))
+                    .collect();
+                if places.len() == 1 {
+                    let point = places[0];
+                    return Some(Deduction {
+                        technique: Technique::HiddenSingle,
+                        cells: unit.cells.clone(),
+                        step: Step::Place(point, Element(value)),
+                        reason: format!(
+                            "{} is the only cell in {} that can hold {}.",
+                            cell_name(point),
+                            unit.name(),
+                            value
+                        ),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// `size` cells in a unit that share exactly `size` candidates between
+    /// them claim those values, so no other cell in the unit can have them.
+    fn naked_subset(&self, size: usize) -> Option<Deduction> {
+        let technique = if size == 2 {
+            Technique::NakedPair
+        } else {
+            Technique::NakedTriple
+        };
+        for unit in &self.units {
+            let cells: Vec<Point> = self
+                .empty_cells(&unit.cells)
+                .filter(|&point| {
+                    let count = self.candidates(point).count_ones() as usize;
+                    count >= 2 && count <= size
+                })
+                .collect();
+            for subset in combinations(&cells, size) {
+                let mask = subset
+                    .iter()
+                    .fold(0, |mask, &point| mask | self.candidates(point));
+                if mask.count_ones() as usize != size {
+                    continue;
+                }
+                let values = bits(mask);
+                let others: Vec<Point> = self
+                    .empty_cells(&unit.cells)
+                    .filter(|point| !subset.contains(point))
+                    .collect();
+                let eliminations = self.eliminations(&others, &values);
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique,
+                        reason: format!(
+                            "{} in {} must hold {} between them, so no other cell in {} can.",
+                            cell_names(&subset),
+                            unit.name(),
+                            value_names(&values),
+                            unit.name()
+                        ),
+                        cells: subset,
+                        step: Step::Eliminate(eliminations),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// `size` values that can only go in the same `size` cells of a unit
+    /// must occupy those cells, so every other candidate there can go.
+    fn hidden_subset(&self, size: usize) -> Option<Deduction> {
+        let technique = if size == 2 {
+            Technique::HiddenPair
+        } else {
+            Technique::HiddenTriple
+        };
+        for unit in &self.units {
+            let values: Vec<u8> = (1..=self.dimension())
+                .filter(|&value| {
+                    let count = self
+                        .empty_cells(&unit.cells)
+                        .filter(|&point| self.has_candidate(point, value))
+                        .count();
+                    count >= 1 && count <= size
+                })
+                .collect();
+            for subset in combinations(&values, size) {
+                let cells: Vec<Point> = self
+                    .empty_cells(&unit.cells)
+                    .filter(|&point| subset.iter().any(|&value| self.has_candidate(point, value)))
+                    .collect();
+                if cells.len() != size {
+                    continue;
+                }
+                let others: Vec<u8> = (1..=self.dimension())
+                    .filter(|value| !subset.contains(value))
+                    .collect();
+                let eliminations = self.eliminations(&cells, &others);
+                if !eliminations.is_empty() {
+                    return Some(Deduction {
+                        technique,
+                        reason: format!(
+                            "{} can only go in {} within {}, so those cells can't hold anything else.",
+                            value_names(&subset),
+                            cell_names(&cells),
+                            unit.name()
+                        ),
+                        cells,
+                        step: Step::Eliminate(eliminations),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// When a value's only spots in a box all sit on one row or column, the
+    /// value can be removed from the rest of that line.
+    fn pointing_pair(&self) -> Option<Deduction> {
+        let boxes = self.units.iter().filter(|u| u.kind == UnitKind::Section);
+        for section in boxes {
+            for value in 1..=self.dimension() {
+                let cells: Vec<Point> = self
+                    .empty_cells(&section.cells)
+                    .filter(|&point| self.has_candidate(point, value))
+                    .collect();
+                if cells.len() < 2 {
+                    continue;
+                }
+                let lines = self
+                    .units
+                    .iter()
+                    .filter(|u| u.kind != UnitKind::Section)
+                    .filter(|u| cells.iter().all(|point| u.cells.contains(point)));
+                for line in lines {
+                    let others: Vec<Point> = self
+                        .empty_cells(&line.cells)
+                        .filter(|point| !section.cells.contains(point))
+                        .collect();
+                    let eliminations = self.eliminations(&others, &[value]);
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::PointingPair,
+                            reason: format!(
+                                "{} can only go in {} within {}, so it can't go anywhere else in {}.",
+                                value,
+                                cell_names(&cells),
+                                section.name(),
+                                line.name()
+                            ),
+                            cells: cells.clone(),
+                            step: Step::Eliminate(eliminations),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// When a value's only spots in a row or column all sit in one box, the
+    /// value can be removed from the rest of that box.
+    fn box_line_reduction(&self) -> Option<Deduction> {
+        let lines = self.units.iter().filter(|u| u.kind != UnitKind::Section);
+        for line in lines {
+            for value in 1..=self.dimension() {
+                let cells: Vec<Point> = self
+                    .empty_cells(&line.cells)
+                    .filter(|&point| self.has_candidate(point, value))
+                    .collect();
+                if cells.len() < 2 {
+                    continue;
+                }
+                let section = self
+                    .units
+                    .iter()
+                    .filter(|u| u.kind == UnitKind::Section)
+                    .find(|u| cells.iter().all(|point| u.cells.contains(point)));
+                if let Some(section) = section {
+                    let others: Vec<Point> = self
+                        .empty_cells(&section.cells)
+                        .filter(|point| !line.cells.contains(point))
+                        .collect();
+                    let eliminations = self.eliminations(&others, &[value]);
+                    if !eliminations.is_empty() {
+                        return Some(Deduction {
+                            technique: Technique::BoxLineReduction,
+                            reason: format!(
+                                "{} can only go in {} within {}, so it can't go anywhere else in {}.",
+                                value,
+                                cell_names(&cells),
+                                line.name(),
+                                section.name()
+                            ),
+                            cells,
+                            step: Step::Eliminate(eliminations),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// When a value can only go in the same two columns of two different
+    /// rows, one of each pair of corners must hold it, so it can be removed
+    /// from the rest of both columns. The same holds with rows and columns
+    /// swapped.
+    fn x_wing(&self) -> Option<Deduction> {
+        for &(kind, cross_kind) in &[
+            (UnitKind::Row, UnitKind::Column),
+            (UnitKind::Column, UnitKind::Row),
+        ] {
+            let lines: Vec<&Unit> = self.units.iter().filter(|u| u.kind == kind).collect();
+            for value in 1..=self.dimension() {
+                let mut positions: Vec<(&Unit, Vec<u8>)> = vec![];
+                for &line in &lines {
+                    let spots: Vec<u8> = (0..line.cells.len())
+                        .filter(|&i| {
+                            let point = line.cells[i];
+                            self.value(point).is_none() && self.has_candidate(point, value)
+                        })
+                        .map(|i| i as u8)
+                        .collect();
+                    if spots.len() == 2 {
+                        positions.push((line, spots));
+                    }
+                }
+                for pair in combinations(&(0..positions.len()).collect::<Vec<_>>(), 2) {
+                    let (first, ref first_spots) = positions[pair[0]];
+                    let (second, ref second_spots) = positions[pair[1]];
+                    if first_spots != second_spots {
+                        continue;
+                    }
+                    let cross_lines: Vec<&Unit> = self
+                        .units
+                        .iter()
+                        .filter(|u| u.kind == cross_kind && first_spots.contains(&u.index))
+                        .collect();
+                    let others: Vec<Point> = cross_lines
+                        .iter()
+                        .flat_map(|&u| self.empty_cells(&u.cells))
+                        .filter(|point| {
+                            !first.cells.contains(point) && !second.cells.contains(point)
+                        })
+                        .collect();
+                    let eliminations = self.eliminations(&others, &[value]);
+                    if !eliminations.is_empty() {
+                        let corners: Vec<Point> = first_spots
+                            .iter()
+                            .flat_map(|&i| vec![first.cells[i as usize], second.cells[i as usize]])
+                            .collect();
+                        return Some(Deduction {
+                            technique: Technique::XWing,
+                            reason: format!(
+                                "{} can only go in the corners {} of {} and {}, so it can't go anywhere else in {} or {}.",
+                                value,
+                                cell_names(&corners),
+                                first.name(),
+                                second.name(),
+                                cross_lines[0].name(),
+                                cross_lines[1].name()
+                            ),
+                            cells: corners,
+                            step: Step::Eliminate(eliminations),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::grid;
+    use super::*;
+
+    const PUZZLE: &str =
+        "530070000600195000098000060800060003400803001700020006060000280000419005000080079";
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    fn parse(order: u8, cells: &str) -> Grid {
+        let values: Vec<u8> = cells
+            .chars()
+            .map(|c| grid::from_glyph(c).unwrap_or(0))
+            .collect();
+        Grid::from_values(order, &values).unwrap()
+    }
+
+    fn empty_solver(order: u8) -> LogicalSolver {
+        LogicalSolver::new(&Grid::empty(order), &RuleSet::classic(order))
+    }
+
+    fn remove_candidate(solver: &mut LogicalSolver, points: &[Point], value: u8) {
+        for &point in points {
+            let index = solver.index(point);
+            solver.candidates[index] &= !(1 << value);
+        }
+    }
+
+    fn eliminations(deduction: &Deduction) -> Vec<(Point, u8)> {
+        match deduction.step {
+            Step::Eliminate(ref eliminations) => eliminations
+                .iter()
+                .map(|&(point, Element(value))| (point, value))
+                .collect(),
+            Step::Place(..) => panic!("expected eliminations"),
+        }
+    }
+
+    fn row(y: u8, xs: &[u8]) -> Vec<Point> {
+        xs.iter().map(|&x| Point(x, y)).collect()
+    }
+
+    #[test]
+    fn naked_single() {
+        let cells = format!("{}.{}", &SOLUTION[..40], &SOLUTION[41..]);
+        let solver = LogicalSolver::new(&parse(3, &cells), &RuleSet::classic(3));
+        let deduction = solver.next_deduction().unwrap();
+        assert_eq!(deduction.technique, Technique::NakedSingle);
+        assert_eq!(deduction.placement(), Some((Point(4, 4), Element(5))));
+    }
+
+    #[test]
+    fn hidden_single() {
+        let mut solver = empty_solver(2);
+        remove_candidate(&mut solver, &row(0, &[1, 2, 3]), 1);
+        let deduction = solver.next_deduction().unwrap();
+        assert_eq!(deduction.technique, Technique::HiddenSingle);
+        assert_eq!(deduction.placement(), Some((Point(0, 0), Element(1))));
+    }
+
+    #[test]
+    fn naked_pair() {
+        let mut solver = empty_solver(2);
+        remove_candidate(&mut solver, &row(0, &[0, 1]), 3);
+        remove_candidate(&mut solver, &row(0, &[0, 1]), 4);
+        let deduction = solver.next_deduction().unwrap();
+        assert_eq!(deduction.technique, Technique::NakedPair);
+        assert_eq!(deduction.cells, row(0, &[0, 1]));
+        let eliminations = eliminations(&deduction);
+        assert!(eliminations.contains(&(Point(2, 0), 1)));
+        assert!(eliminations.contains(&(Point(3, 0), 2)));
+    }
+
+    #[test]
+    fn hidden_pair() {
+        let mut solver = empty_solver(2);
+        remove_candidate(&mut solver, &row(0, &[2, 3]), 1);
+        remove_candidate(&mut solver, &row(0, &[2, 3]), 2);
+        let deduction = solver.hidden_subset(2).unwrap();
+        assert_eq!(deduction.technique, Technique::HiddenPair);
+        assert_eq!(deduction.cells, row(0, &[0, 1]));
+        let eliminations = eliminations(&deduction);
+        assert!(eliminations.contains(&(Point(0, 0), 3)));
+        assert!(eliminations.contains(&(Point(1, 0), 4)));
+    }
+
+    #[test]
+    fn naked_triple() {
+        let mut solver = empty_solver(3);
+        for &(x, ref values) in &[(0, [1, 2]), (1, [2, 3]), (2, [1, 3])] {
+            let index = solver.index(Point(x, 0));
+            solver.candidates[index] = values.iter().fold(0, |mask, value| mask | 1 << value);
+        }
+        let deduction = solver.naked_subset(3).unwrap();
+        assert_eq!(deduction.technique, Technique::NakedTriple);
+        assert_eq!(deduction.cells, row(0, &[0, 1, 2]));
+        assert!(eliminations(&deduction).contains(&(Point(5, 0), 3)));
+    }
+
+    #[test]
+    fn hidden_triple() {
+        let mut solver = empty_solver(3);
+        for value in 1..=3 {
+            remove_candidate(&mut solver, &row(0, &[3, 4, 5, 6, 7, 8]), value);
+        }
+        let deduction = solver.hidden_subset(3).unwrap();
+        assert_eq!(deduction.technique, Technique::HiddenTriple);
+        assert_eq!(deduction.cells, row(0, &[0, 1, 2]));
+        assert!(eliminations(&deduction).contains(&(Point(1, 0), 9)));
+    }
+
+    #[test]
+    fn pointing_pair() {
+        let mut solver = empty_solver(3);
+        remove_candidate(&mut solver, &row(1, &[0, 1, 2]), 1);
+        remove_candidate(&mut solver, &row(2, &[0, 1, 2]), 1);
+        let deduction = solver.pointing_pair().unwrap();
+        assert_eq!(deduction.technique, Technique::PointingPair);
+        let eliminations = eliminations(&deduction);
+        assert!(eliminations.contains(&(Point(5, 0), 1)));
+        assert!(!eliminations.contains(&(Point(0, 0), 1)));
+    }
+
+    #[test]
+    fn box_line_reduction() {
+        let mut solver = empty_solver(3);
+        remove_candidate(&mut solver, &row(0, &[3, 4, 5, 6, 7, 8]), 1);
+        let deduction = solver.box_line_reduction().unwrap();
+        assert_eq!(deduction.technique, Technique::BoxLineReduction);
+        let eliminations = eliminations(&deduction);
+        assert!(eliminations.contains(&(Point(2, 2), 1)));
+        assert!(!eliminations.contains(&(Point(2, 0), 1)));
+        assert!(!eliminations.contains(&(Point(4, 1), 1)));
+    }
+
+    #[test]
+    fn x_wing() {
+        let mut solver = empty_solver(3);
+        for &y in &[0, 4] {
+            remove_candidate(&mut solver, &row(y, &[0, 2, 3, 4, 5, 6, 8]), 1);
+        }
+        let deduction = solver.x_wing().unwrap();
+        assert_eq!(deduction.technique, Technique::XWing);
+        assert_eq!(
+            deduction.cells,
+            vec![Point(1, 0), Point(1, 4), Point(7, 0), Point(7, 4)]
+        );
+        let eliminations = eliminations(&deduction);
+        assert!(eliminations.contains(&(Point(1, 2), 1)));
+        assert!(eliminations.contains(&(Point(7, 8), 1)));
+        assert!(!eliminations.contains(&(Point(1, 4), 1)));
+    }
+
+    #[test]
+    fn hints_agree_with_the_solution() {
+        let (puzzle, solution) = (parse(3, PUZZLE), parse(3, SOLUTION));
+        let hint = find_hint(&puzzle, &solution, &RuleSet::classic(3)).unwrap();
+        let (point, value) = hint.placement().unwrap();
+        assert_eq!(solution[point], Some(value));
+    }
+
+    #[test]
+    fn no_hint_from_a_wrong_entry() {
+        let (mut current, solution) = (parse(3, PUZZLE), parse(3, SOLUTION));
+        // Breaks no rule, but the solution has a 4 here
+        current.set(Point(2, 0), Some(Element(1)));
+        match find_hint(&current, &solution, &RuleSet::classic(3)) {
+            Err(HintError::Mistakes) => {}
+            other => panic!("expected a mistake, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn no_hint_on_a_solved_board() {
+        let solution = parse(3, SOLUTION);
+        match find_hint(&solution, &solution, &RuleSet::classic(3)) {
+            Err(HintError::Solved) => {}
+            other => panic!("expected a solved board, got {:?}", other),
+        }
+    }
+}
@@ -1,7 +1,35 @@
 mod ability;
+mod cages;
 mod character;
+mod edges;
+pub mod export;
 mod gameboard;
+mod generator;
+mod grid;
+mod history;
+pub mod import;
+mod logic;
+mod notes;
+mod rating;
+mod regions;
+mod rules;
+mod save;
+mod solver;
+mod tactics;
+mod units;
 
 pub use self::ability::*;
+pub use self::cages::*;
 pub use self::character::*;
+pub use self::edges::*;
 pub use self::gameboard::*;
+pub use self::generator::{can_deal, fresh_seed};
+pub use self::grid::*;
+pub use self::history::*;
+pub use self::logic::*;
+pub use self::notes::*;
+pub use self::rating::*;
+pub use self::regions::*;
+pub use self::rules::*;
+pub use self::save::*;
+pub use self::tactics::*;
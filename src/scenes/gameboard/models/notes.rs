@@ -0,0 +1,57 @@
+use super::gameboard::Point;
+use sudoku::Element;
+
+/// Pencil-mark candidates for every cell on the board, stored as one bitmask
+/// per cell where bit `n` is set when `n` has been noted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notes {
+    dimension: u8,
+    cells: Vec<u32>,
+}
+
+impl Notes {
+    pub fn new(order: u8) -> Self {
+        let dimension = order * order;
+        Notes {
+            dimension,
+            cells: vec![0; dimension as usize * dimension as usize],
+        }
+    }
+
+    /// Whether these are the notes for a board of `order`.
+    pub fn fits(&self, order: u8) -> bool {
+        let dimension = order as usize * order as usize;
+        self.dimension as usize == dimension && self.cells.len() == dimension * dimension
+    }
+
+    fn index(&self, point: Point) -> usize {
+        let Point(x, y) = point;
+        y as usize * self.dimension as usize + x as usize
+    }
+
+    pub fn toggle(&mut self, point: Point, value: Element) {
+        let Element(value) = value;
+        let index = self.index(point);
+        self.cells[index] ^= 1 << value;
+    }
+
+    pub fn remove(&mut self, point: Point, value: Element) {
+        let Element(value) = value;
+        let index = self.index(point);
+        self.cells[index] &= !(1 << value);
+    }
+
+    pub fn clear(&mut self, point: Point) {
+        let index = self.index(point);
+        self.cells[index] = 0;
+    }
+
+    /// The noted values for `point`, in ascending order.
+    pub fn values(&self, point: Point) -> Vec<Element> {
+        let mask = self.cells[self.index(point)];
+        (1..=self.dimension)
+            .filter(|value| mask & (1 << value) != 0)
+            .map(Element)
+            .collect()
+    }
+}
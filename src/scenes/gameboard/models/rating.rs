@@ -0,0 +1,69 @@
+use super::grid::Grid;
+use super::logic::{LogicalSolver, Technique};
+use super::rules::RuleSet;
+
+/// How hard a puzzle is for a person, judged by the hardest technique it
+/// takes to solve it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Rating {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    /// Needs something beyond the techniques the logical solver knows.
+    Extreme,
+}
+
+impl Rating {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rating::Easy => "Easy",
+            Rating::Medium => "Medium",
+            Rating::Hard => "Hard",
+            Rating::Expert => "Expert",
+            Rating::Extreme => "Extreme",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "easy" => Some(Rating::Easy),
+            "medium" => Some(Rating::Medium),
+            "hard" => Some(Rating::Hard),
+            "expert" => Some(Rating::Expert),
+            "extreme" => Some(Rating::Extreme),
+            _ => None,
+        }
+    }
+
+    fn for_technique(technique: Technique) -> Self {
+        match technique {
+            Technique::NakedSingle | Technique::HiddenSingle => Rating::Easy,
+            Technique::NakedPair
+            | Technique::HiddenPair
+            | Technique::NakedTriple
+            | Technique::HiddenTriple => Rating::Medium,
+            Technique::PointingPair | Technique::BoxLineReduction => Rating::Hard,
+            Technique::XWing => Rating::Expert,
+        }
+    }
+
+    pub fn distance(&self, other: Rating) -> u32 {
+        (*self as i32 - other as i32).abs() as u32
+    }
+}
+
+/// Solves `grid` using only human techniques and rates it by the hardest
+/// one that was needed.
+pub fn rate(grid: &Grid, rules: &RuleSet) -> Rating {
+    let mut solver = LogicalSolver::new(grid, rules);
+    let mut rating = Rating::Easy;
+    while let Some(deduction) = solver.step() {
+        rating = rating.max(Rating::for_technique(deduction.technique));
+    }
+    if solver.is_solved() {
+        rating
+    } else {
+        Rating::Extreme
+    }
+}
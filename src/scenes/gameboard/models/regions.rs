@@ -0,0 +1,202 @@
+//! The regions of a board: the groups of cells that, like rows and columns,
+//! must each hold every value once. On a classic board they are square
+//! sections; in jigsaw puzzles they can be any connected shape.
+
+use super::gameboard::Point;
+use super::generator::Random;
+use super::grid::Grid;
+use super::units;
+
+/// How many times to try reshaping a pair of regions when dealing a jigsaw
+/// layout, per cell on the board.
+const RESHAPES_PER_CELL: usize = 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Regions {
+    order: u8,
+    /// The region each cell belongs to, row by row.
+    cells: Vec<u8>,
+}
+
+impl Regions {
+    pub fn square(order: u8) -> Self {
+        let dimension = order * order;
+        let cells = (0..dimension)
+            .flat_map(|y| (0..dimension).map(move |x| y / order * order + x / order))
+            .collect();
+        Regions { order, cells }
+    }
+
+    /// Checks that every region has exactly one cell per value and that its
+    /// cells are connected.
+    pub fn from_cells(order: u8, cells: Vec<u8>) -> Option<Self> {
+        let dimension = order * order;
+        if cells.len() != dimension as usize * dimension as usize {
+            return None;
+        }
+        let regions = Regions { order, cells };
+        let is_valid = (0..dimension).all(|region| {
+            regions.region(region).len() == dimension as usize && regions.is_connected(region)
+        });
+        if is_valid {
+            Some(regions)
+        } else {
+            None
+        }
+    }
+
+    /// Reshapes the square sections of a filled grid by repeatedly swapping a
+    /// cell on the edge of one region for a cell on the edge of its
+    /// neighbour. Both cells hold the same value, so `solution` stays valid,
+    /// and a swap is only kept if it leaves both regions in one piece.
+    pub fn random(solution: &Grid, random: &mut Random) -> Self {
+        let mut regions = Regions::square(solution.order());
+        for _ in 0..regions.cells.len() * RESHAPES_PER_CELL {
+            let points = regions.points();
+            let a = points[random.below(points.len())];
+            let neighbours: Vec<Point> = regions
+                .neighbours(a)
+                .into_iter()
+                .filter(|&point| regions.region_of(point) != regions.region_of(a))
+                .collect();
+            if neighbours.is_empty() {
+                continue;
+            }
+            let (from, to) = (
+                regions.region_of(a),
+                regions.region_of(neighbours[random.below(neighbours.len())]),
+            );
+            // Each region holds every value once, so there is only one cell of
+            // `to` that `a` can trade places with
+            let b = regions
+                .region(to)
+                .into_iter()
+                .find(|&point| solution[point] == solution[a])
+                .unwrap();
+            let borders_from = regions
+                .neighbours(b)
+                .iter()
+                .any(|&n| n != a && regions.region_of(n) == from);
+            if !borders_from {
+                continue;
+            }
+            regions.set(a, to);
+            regions.set(b, from);
+            if !regions.is_connected(from) || !regions.is_connected(to) {
+                regions.set(a, from);
+                regions.set(b, to);
+            }
+        }
+        regions
+    }
+
+    pub fn order(&self) -> u8 {
+        self.order
+    }
+
+    fn dimension(&self) -> u8 {
+        self.order * self.order
+    }
+
+    fn offset(&self, point: Point) -> usize {
+        let Point(x, y) = point;
+        y as usize * self.dimension() as usize + x as usize
+    }
+
+    fn set(&mut self, point: Point, region: u8) {
+        let offset = self.offset(point);
+        self.cells[offset] = region;
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let dimension = self.dimension();
+        (0..dimension)
+            .flat_map(|y| (0..dimension).map(move |x| Point(x, y)))
+            .collect()
+    }
+
+    /// The inverse of `from_cells`.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn region_of(&self, point: Point) -> u8 {
+        self.cells[self.offset(point)]
+    }
+
+    /// The cells of a region, row by row.
+    pub fn region(&self, region: u8) -> Vec<Point> {
+        self.points()
+            .into_iter()
+            .filter(|&point| self.region_of(point) == region)
+            .collect()
+    }
+
+    pub fn all(&self) -> Vec<Vec<Point>> {
+        (0..self.dimension())
+            .map(|region| self.region(region))
+            .collect()
+    }
+
+    pub fn is_square(&self) -> bool {
+        *self == Regions::square(self.order)
+    }
+
+    fn neighbours(&self, point: Point) -> Vec<Point> {
+        units::neighbours(point, self.dimension())
+    }
+
+    fn is_connected(&self, region: u8) -> bool {
+        let cells = self.region(region);
+        let mut reached = vec![cells[0]];
+        let mut i = 0;
+        while i < reached.len() {
+            for next in self.neighbours(reached[i]) {
+                if self.region_of(next) == region && !reached.contains(&next) {
+                    reached.push(next);
+                }
+            }
+            i += 1;
+        }
+        reached.len() == cells.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::rules::RuleSet;
+    use super::super::solver;
+    use super::*;
+    use sudoku::Element;
+
+    #[test]
+    fn checks_cells_make_whole_regions() {
+        let layout = vec![0, 0, 1, 1, 0, 2, 1, 1, 0, 2, 3, 3, 2, 2, 3, 3];
+        let regions = Regions::from_cells(2, layout).unwrap();
+        assert!(!regions.is_square());
+        assert_eq!(regions.region_of(Point(1, 1)), 2);
+
+        // Region 0 is one cell short
+        let uneven = vec![0, 2, 1, 1, 0, 2, 1, 1, 0, 2, 3, 3, 2, 2, 3, 3];
+        assert!(Regions::from_cells(2, uneven).is_none());
+        // Region 1 is split in two
+        let split = vec![0, 0, 1, 1, 0, 0, 2, 2, 2, 2, 3, 3, 1, 1, 3, 3];
+        assert!(Regions::from_cells(2, split).is_none());
+        assert!(Regions::from_cells(2, vec![0; 15]).is_none());
+    }
+
+    #[test]
+    fn reshapes_without_breaking_the_solution() {
+        let mut random = Random::new(7);
+        let solution = solver::random_solution(&RuleSet::classic(3), &mut random, 1000).unwrap();
+        let regions = Regions::random(&solution, &mut random);
+        assert!(!regions.is_square());
+        assert!(Regions::from_cells(3, regions.cells().to_vec()).is_some());
+        for region in regions.all() {
+            let mut values: Vec<Element> =
+                region.iter().filter_map(|&point| solution[point]).collect();
+            values.sort();
+            assert_eq!(values, (1..=9).map(Element).collect::<Vec<_>>());
+        }
+    }
+}
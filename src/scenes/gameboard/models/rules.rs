@@ -0,0 +1,254 @@
+//! The rules a board is played by. Every puzzle needs each row, column and
+//! region to hold every value once; on top of that a puzzle can add any
+//! number of extra rules. Anything that checks or searches the board asks
+//! the rule set rather than knowing about the rules itself.
+
+use super::gameboard::Point;
+use super::generator::Random;
+use super::grid::Grid;
+use super::regions::Regions;
+use super::units;
+use std::collections::HashSet;
+use sudoku::Element;
+
+/// How many cells an odd/even rule shades, as a fraction of the board.
+const SHADED_CELLS_PER_CELL: f32 = 0.2;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Parity {
+    Odd,
+    Even,
+}
+
+impl Parity {
+    pub fn of(value: u8) -> Self {
+        if value % 2 == 1 {
+            Parity::Odd
+        } else {
+            Parity::Even
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Rule {
+    /// Both main diagonals must hold every value once, as in X-Sudoku.
+    Diagonals,
+    /// Cells a chess knight's move apart can't hold the same value.
+    AntiKnight,
+    /// Shaded cells must hold a value of the given parity. A rule with no
+    /// cells is shaded when the puzzle is dealt.
+    OddEven(Vec<(Point, Parity)>),
+}
+
+impl Rule {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Rule::Diagonals => "Diagonal",
+            Rule::AntiKnight => "Anti-Knight",
+            Rule::OddEven(_) => "Odd/Even",
+        }
+    }
+
+    /// Groups of cells, beyond rows, columns and regions, that must hold
+    /// every value once.
+    fn units(&self, order: u8) -> Vec<Vec<Point>> {
+        match *self {
+            Rule::Diagonals => units::diagonals(order),
+            _ => vec![],
+        }
+    }
+
+    /// Cells that can't share a value with `point` without making up a
+    /// whole unit with it.
+    fn exclusions(&self, point: Point, order: u8) -> Vec<Point> {
+        match *self {
+            Rule::AntiKnight => knight_moves(point, order * order),
+            _ => vec![],
+        }
+    }
+
+    fn allows(&self, point: Point, value: u8) -> bool {
+        match *self {
+            Rule::OddEven(ref cells) => cells
+                .iter()
+                .all(|&(cell, parity)| cell != point || parity == Parity::of(value)),
+            _ => true,
+        }
+    }
+}
+
+fn knight_moves(point: Point, dimension: u8) -> Vec<Point> {
+    let Point(x, y) = point;
+    let (x, y, dimension) = (i16::from(x), i16::from(y), i16::from(dimension));
+    let jumps = [
+        (1, 2),
+        (2, 1),
+        (2, -1),
+        (1, -2),
+        (-1, -2),
+        (-2, -1),
+        (-2, 1),
+        (-1, 2),
+    ];
+    jumps
+        .iter()
+        .map(|&(dx, dy)| (x + dx, y + dy))
+        .filter(|&(x, y)| x >= 0 && y >= 0 && x < dimension && y < dimension)
+        .map(|(x, y)| Point(x as u8, y as u8))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSet {
+    regions: Regions,
+    rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn new(regions: Regions, rules: Vec<Rule>) -> Self {
+        RuleSet { regions, rules }
+    }
+
+    /// Square sections and nothing else.
+    pub fn classic(order: u8) -> Self {
+        Self::new(Regions::square(order), vec![])
+    }
+
+    pub fn order(&self) -> u8 {
+        self.regions.order()
+    }
+
+    pub fn regions(&self) -> &Regions {
+        &self.regions
+    }
+
+    pub fn set_regions(&mut self, regions: Regions) {
+        self.regions = regions;
+    }
+
+    /// The extra rules, not counting the classic ones.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Every row, column and region, followed by any units the extra rules
+    /// add.
+    pub fn units(&self) -> Vec<Vec<Point>> {
+        let order = self.order();
+        let dimension = order * order;
+        let rows = (0..dimension).map(|y| units::row(y, order));
+        let columns = (0..dimension).map(|x| units::column(x, order));
+        rows.chain(columns)
+            .chain(self.regions.all())
+            .chain(self.extra_units())
+            .collect()
+    }
+
+    pub fn extra_units(&self) -> Vec<Vec<Point>> {
+        let order = self.order();
+        self.rules
+            .iter()
+            .flat_map(|rule| rule.units(order))
+            .collect()
+    }
+
+    /// Cells that can't share a value with `point` outside of any unit.
+    pub fn exclusions(&self, point: Point) -> Vec<Point> {
+        let order = self.order();
+        self.rules
+            .iter()
+            .flat_map(|rule| rule.exclusions(point, order))
+            .collect()
+    }
+
+    /// Every cell that can't hold the same value as `point`, not including
+    /// `point` itself.
+    pub fn peers(&self, point: Point) -> Vec<Point> {
+        let mut points: Vec<Point> = self
+            .units()
+            .into_iter()
+            .filter(|unit| unit.contains(&point))
+            .flat_map(|unit| unit.into_iter())
+            .chain(self.exclusions(point))
+            .filter(|&peer| peer != point)
+            .collect();
+        points.sort_by_key(|&Point(x, y)| (y, x));
+        points.dedup();
+        points
+    }
+
+    /// Whether `point` may hold `value` regardless of what else is on the
+    /// board.
+    pub fn allows(&self, point: Point, value: u8) -> bool {
+        self.rules.iter().all(|rule| rule.allows(point, value))
+    }
+
+    /// Cells that break a rule: values repeated in a unit or across an
+    /// exclusion, and values a cell may not hold at all.
+    pub fn conflicts(&self, grid: &Grid) -> HashSet<Point> {
+        let mut conflicts = HashSet::new();
+        for unit in self.units() {
+            for (i, &point) in unit.iter().enumerate() {
+                let value = match grid[point] {
+                    Some(value) => value,
+                    None => continue,
+                };
+                for &other in &unit[i + 1..] {
+                    if grid[other] == Some(value) {
+                        conflicts.insert(point);
+                        conflicts.insert(other);
+                    }
+                }
+            }
+        }
+        for point in grid.points() {
+            if let Some(Element(value)) = grid[point] {
+                if !self.allows(point, value) {
+                    conflicts.insert(point);
+                }
+                for other in self.exclusions(point) {
+                    if grid[other] == Some(Element(value)) {
+                        conflicts.insert(point);
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+
+    /// Picks the cells of any odd/even rule that has none yet, shading them
+    /// to match `solution`.
+    pub fn shade(&mut self, solution: &Grid, random: &mut Random) {
+        for rule in &mut self.rules {
+            if let Rule::OddEven(ref mut cells) = *rule {
+                if !cells.is_empty() {
+                    continue;
+                }
+                let mut points = solution.points();
+                random.shuffle(&mut points);
+                let count = (points.len() as f32 * SHADED_CELLS_PER_CELL) as usize;
+                for &point in &points[..count] {
+                    if let Some(Element(value)) = solution[point] {
+                        cells.push((point, Parity::of(value)));
+                    }
+                }
+                cells.sort_by_key(|&(Point(x, y), _)| (y, x));
+            }
+        }
+    }
+
+    /// The parity a cell is shaded with, if any.
+    pub fn parity(&self, point: Point) -> Option<Parity> {
+        self.rules
+            .iter()
+            .filter_map(|rule| match *rule {
+                Rule::OddEven(ref cells) => cells
+                    .iter()
+                    .find(|&&(cell, _)| cell == point)
+                    .map(|&(_, parity)| parity),
+                _ => None,
+            })
+            .next()
+    }
+}
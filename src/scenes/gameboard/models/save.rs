@@ -0,0 +1,153 @@
+//! Saved games are kept as a single RON file in the user data directory, so
+//! an unfinished battle can be picked back up after the window closes.
+
+use super::ability::AbilityStatus;
+use super::cages::Cage;
+use super::character::{CharacterKind, Stats};
+use super::gameboard::Point;
+use super::notes::Notes;
+use super::rating::Rating;
+use super::rules::Rule;
+use failure;
+use ron;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fields added to `SaveGame` must be `#[serde(default)]`, so that older saves
+/// still load. Bump this only for changes older saves can't be read under,
+/// such as renaming or removing a field, so that they are rejected instead of
+/// being misread.
+pub const SAVE_VERSION: u32 = 1;
+const SAVE_FILE_NAME: &str = "save.ron";
+
+#[derive(Debug, Fail)]
+pub enum SaveError {
+    #[fail(display = "unsupported save version {} (expected {})", found, expected)]
+    UnsupportedVersion { found: u32, expected: u32 },
+    #[fail(display = "save file is corrupt")]
+    Corrupt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoardSave {
+    pub order: u8,
+    pub problem: Vec<u8>,
+    pub current: Vec<u8>,
+    pub solution: Vec<u8>,
+    /// The region of each cell, row by row.
+    #[serde(default)]
+    pub regions: Vec<u8>,
+    #[serde(default)]
+    pub rules: Vec<Rule>,
+    #[serde(default)]
+    pub cages: Vec<Cage>,
+    pub rating: Rating,
+    #[serde(default)]
+    pub seed: Option<u32>,
+    pub notes: Notes,
+    pub moves: usize,
+    #[serde(default)]
+    pub mistakes: usize,
+    #[serde(default)]
+    pub mistake_limit: Option<usize>,
+    #[serde(default)]
+    pub hints_used: usize,
+    #[serde(default)]
+    pub scored: Vec<Point>,
+    pub selected_cell: Option<Point>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbilitySave {
+    pub name: String,
+    pub icon_asset: String,
+    pub status: AbilityStatus,
+    /// Seconds until the status next changes.
+    #[serde(default)]
+    pub remaining: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CharacterSave {
+    pub name: String,
+    pub kind: CharacterKind,
+    pub image_asset: String,
+    pub abilities: Vec<AbilitySave>,
+    #[serde(default)]
+    pub stats: Option<Stats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SaveGame {
+    pub version: u32,
+    pub board: BoardSave,
+    /// Seconds spent on the board so far.
+    pub elapsed: f64,
+    /// The seconds allowed in countdown mode.
+    #[serde(default)]
+    pub time_limit: Option<f64>,
+    pub character: CharacterSave,
+    pub opponent: CharacterSave,
+}
+
+impl SaveGame {
+    fn path(dir: &Path) -> PathBuf {
+        dir.join(SAVE_FILE_NAME)
+    }
+
+    pub fn exists(dir: &Path) -> bool {
+        Self::path(dir).is_file()
+    }
+
+    pub fn load(dir: &Path) -> Result<Self, failure::Error> {
+        let contents = fs::read_to_string(Self::path(dir))?;
+        let save: SaveGame = ron::de::from_str(&contents)?;
+        if save.version != SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion {
+                found: save.version,
+                expected: SAVE_VERSION,
+            }.into());
+        }
+        Ok(save)
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<(), failure::Error> {
+        write_ron(dir, SAVE_FILE_NAME, self)
+    }
+
+    pub fn delete(dir: &Path) -> Result<(), failure::Error> {
+        if Self::exists(dir) {
+            fs::remove_file(Self::path(dir))?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads the RON file `name` in `dir`. Falls back to the default if nothing
+/// has been written yet, or if the file can't be read.
+pub fn load_ron<T: DeserializeOwned + Default>(dir: &Path, name: &str) -> T {
+    let path = dir.join(name);
+    if !path.is_file() {
+        return T::default();
+    }
+    let value = fs::read_to_string(&path)
+        .map_err(failure::Error::from)
+        .and_then(|contents| ron::de::from_str(&contents).map_err(failure::Error::from));
+    match value {
+        Ok(value) => value,
+        Err(e) => {
+            warn!("Could not read {:?}: {}", path, e);
+            T::default()
+        }
+    }
+}
+
+/// Writes `value` to the RON file `name` in `dir`, creating `dir` if needed.
+pub fn write_ron<T: Serialize>(dir: &Path, name: &str, value: &T) -> Result<(), failure::Error> {
+    fs::create_dir_all(dir)?;
+    let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+    fs::write(dir.join(name), contents)?;
+    Ok(())
+}
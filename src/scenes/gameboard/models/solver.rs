@@ -0,0 +1,302 @@
+//! A brute-force backtracking solver. Unlike the logical solver it doesn't
+//! care how a person would approach the puzzle; it is used to check that a
+//! puzzle has exactly one solution, to find it, and to fill new grids.
+
+use super::cages::{self, Cage};
+use super::gameboard::Point;
+use super::generator::Random;
+use super::grid::Grid;
+use super::rules::RuleSet;
+use sudoku::Element;
+
+#[derive(Debug, Clone)]
+struct CageState {
+    used: u32,
+    remaining: u32,
+    empty: u32,
+}
+
+#[derive(Debug, Clone)]
+struct Solver {
+    order: u8,
+    values: Vec<u8>,
+    rows: Vec<u32>,
+    columns: Vec<u32>,
+    sections: Vec<u32>,
+    section_of: Vec<u8>,
+    /// Units added by the rule set, such as diagonals, and the ones each
+    /// cell belongs to.
+    extra_units: Vec<u32>,
+    extra_units_of: Vec<Vec<usize>>,
+    /// The cells each cell can't share a value with outside of any unit.
+    exclusions: Vec<Vec<usize>>,
+    /// The values each cell may hold at all.
+    allowed: Vec<u32>,
+    cage_of: Vec<Option<usize>>,
+    cages: Vec<CageState>,
+    /// How many more guesses the search may make before giving up.
+    budget: Option<usize>,
+}
+
+impl Solver {
+    /// Returns `None` if the givens already break the rules.
+    fn new(grid: &Grid, rules: &RuleSet, cages: &[Cage]) -> Option<Self> {
+        let order = grid.order();
+        let dimension = grid.dimension() as usize;
+        let extra_units = rules.extra_units();
+        let mut solver = Solver {
+            order,
+            values: vec![0; dimension * dimension],
+            rows: vec![0; dimension],
+            columns: vec![0; dimension],
+            sections: vec![0; dimension],
+            section_of: rules.regions().cells().to_vec(),
+            extra_units: vec![0; extra_units.len()],
+            extra_units_of: vec![vec![]; dimension * dimension],
+            exclusions: vec![vec![]; dimension * dimension],
+            allowed: vec![0; dimension * dimension],
+            cage_of: vec![None; dimension * dimension],
+            cages: vec![],
+            budget: None,
+        };
+        for (i, unit) in extra_units.iter().enumerate() {
+            for &point in unit {
+                let cell = solver.indices(point).0;
+                solver.extra_units_of[cell].push(i);
+            }
+        }
+        for point in grid.points() {
+            let cell = solver.indices(point).0;
+            let exclusions = rules
+                .exclusions(point)
+                .into_iter()
+                .map(|other| solver.indices(other).0)
+                .collect();
+            solver.exclusions[cell] = exclusions;
+            solver.allowed[cell] = (1..=grid.dimension())
+                .filter(|&value| rules.allows(point, value))
+                .fold(0, |mask, value| mask | 1 << value);
+        }
+        for (i, cage) in cages.iter().enumerate() {
+            for &point in &cage.cells {
+                let cell = solver.indices(point).0;
+                solver.cage_of[cell] = Some(i);
+            }
+            solver.cages.push(CageState {
+                used: 0,
+                remaining: cage.sum,
+                empty: cage.cells.len() as u32,
+            });
+        }
+        for point in grid.points() {
+            if let Some(Element(value)) = grid[point] {
+                if !solver.allows(point, value) {
+                    return None;
+                }
+                solver.place(point, value);
+            }
+        }
+        Some(solver)
+    }
+
+    fn dimension(&self) -> u8 {
+        self.order * self.order
+    }
+
+    fn indices(&self, point: Point) -> (usize, usize, usize, usize) {
+        let Point(x, y) = point;
+        let cell = y as usize * self.dimension() as usize + x as usize;
+        let section = self.section_of[cell] as usize;
+        (cell, y as usize, x as usize, section)
+    }
+
+    fn candidates(&self, point: Point) -> u32 {
+        let (cell, row, column, section) = self.indices(point);
+        let mut candidates =
+            self.allowed[cell] & !(self.rows[row] | self.columns[column] | self.sections[section]);
+        for &unit in &self.extra_units_of[cell] {
+            candidates &= !self.extra_units[unit];
+        }
+        // Empty cells hold 0, which is never a candidate anyway
+        for &other in &self.exclusions[cell] {
+            candidates &= !(1 << self.values[other]);
+        }
+        if let Some(cage) = self.cage_of[cell] {
+            candidates &= self.cage_candidates(&self.cages[cage]);
+        }
+        candidates
+    }
+
+    /// Values that don't repeat in the cage and still leave a way for the
+    /// rest of its empty cells to make up the sum.
+    fn cage_candidates(&self, cage: &CageState) -> u32 {
+        let dimension = u32::from(self.dimension());
+        let (lowest, highest) = cages::sum_range(cage.empty.saturating_sub(1), dimension);
+        (1..=dimension)
+            .filter(|&value| cage.used & (1 << value) == 0)
+            .filter(|&value| value + lowest <= cage.remaining && value + highest >= cage.remaining)
+            .fold(0, |mask, value| mask | 1 << value)
+    }
+
+    fn allows(&self, point: Point, value: u8) -> bool {
+        self.candidates(point) & (1 << value) != 0
+    }
+
+    fn place(&mut self, point: Point, value: u8) {
+        let (cell, row, column, section) = self.indices(point);
+        self.values[cell] = value;
+        self.rows[row] |= 1 << value;
+        self.columns[column] |= 1 << value;
+        self.sections[section] |= 1 << value;
+        for &unit in &self.extra_units_of[cell] {
+            self.extra_units[unit] |= 1 << value;
+        }
+        if let Some(cage) = self.cage_of[cell] {
+            let cage = &mut self.cages[cage];
+            cage.used |= 1 << value;
+            cage.remaining -= u32::from(value);
+            cage.empty -= 1;
+        }
+    }
+
+    fn unplace(&mut self, point: Point, value: u8) {
+        let (cell, row, column, section) = self.indices(point);
+        self.values[cell] = 0;
+        self.rows[row] &= !(1 << value);
+        self.columns[column] &= !(1 << value);
+        self.sections[section] &= !(1 << value);
+        for &unit in &self.extra_units_of[cell] {
+            self.extra_units[unit] &= !(1 << value);
+        }
+        if let Some(cage) = self.cage_of[cell] {
+            let cage = &mut self.cages[cage];
+            cage.used &= !(1 << value);
+            cage.remaining += u32::from(value);
+            cage.empty += 1;
+        }
+    }
+
+    /// The empty cell with the fewest candidates, which keeps the search
+    /// tree as narrow as possible.
+    fn most_constrained(&self) -> Option<(Point, u32)> {
+        let dimension = self.dimension();
+        let mut best: Option<(Point, u32)> = None;
+        for y in 0..dimension {
+            for x in 0..dimension {
+                let point = Point(x, y);
+                if self.values[self.indices(point).0] != 0 {
+                    continue;
+                }
+                let candidates = self.candidates(point);
+                let is_better = best.map_or(true, |(_, best)| {
+                    candidates.count_ones() < best.count_ones()
+                });
+                if is_better {
+                    best = Some((point, candidates));
+                }
+            }
+        }
+        best
+    }
+
+    /// Returns false if the search ran out of budget before finishing.
+    fn search(&mut self, limit: usize, solutions: &mut Vec<Grid>) -> bool {
+        let (point, candidates) = match self.most_constrained() {
+            Some(cell) => cell,
+            None => {
+                solutions.push(self.to_grid());
+                return true;
+            }
+        };
+        for value in 1..=self.dimension() {
+            if candidates & (1 << value) == 0 {
+                continue;
+            }
+            if !self.spend() {
+                return false;
+            }
+            self.place(point, value);
+            let finished = self.search(limit, solutions);
+            self.unplace(point, value);
+            if !finished {
+                return false;
+            }
+            if solutions.len() >= limit {
+                return true;
+            }
+        }
+        true
+    }
+
+    /// Uses up one guess, returning false if there were none left.
+    fn spend(&mut self) -> bool {
+        match self.budget {
+            Some(0) => false,
+            Some(ref mut budget) => {
+                *budget -= 1;
+                true
+            }
+            None => true,
+        }
+    }
+
+    /// Fills every empty cell, trying values in a random order. Returns
+    /// whether it managed to.
+    fn fill(&mut self, random: &mut Random) -> bool {
+        let (point, candidates) = match self.most_constrained() {
+            Some(cell) => cell,
+            None => return true,
+        };
+        let mut values: Vec<u8> = (1..=self.dimension())
+            .filter(|&value| candidates & (1 << value) != 0)
+            .collect();
+        random.shuffle(&mut values);
+        for value in values {
+            if !self.spend() {
+                return false;
+            }
+            self.place(point, value);
+            if self.fill(random) {
+                return true;
+            }
+            self.unplace(point, value);
+        }
+        false
+    }
+
+    fn to_grid(&self) -> Grid {
+        Grid::from_values(self.order, &self.values).unwrap()
+    }
+}
+
+/// Finds up to `limit` solutions to `grid` under `rules` and `cages`,
+/// giving up and returning `None` after `budget` guesses. Some puzzles,
+/// especially on large boards, take a very long time to prove unique.
+pub fn solutions_within(
+    grid: &Grid,
+    rules: &RuleSet,
+    cages: &[Cage],
+    limit: usize,
+    budget: usize,
+) -> Option<Vec<Grid>> {
+    let mut solutions = vec![];
+    if let Some(mut solver) = Solver::new(grid, rules, cages) {
+        solver.budget = Some(budget);
+        if !solver.search(limit, &mut solutions) {
+            return None;
+        }
+    }
+    Some(solutions)
+}
+
+/// A complete grid picked by `random`, or `None` if one couldn't be found
+/// within `budget` guesses.
+pub fn random_solution(rules: &RuleSet, random: &mut Random, budget: usize) -> Option<Grid> {
+    let mut solver = Solver::new(&Grid::empty(rules.order()), rules, &[]).unwrap();
+    solver.budget = Some(budget);
+    if solver.fill(random) {
+        Some(solver.to_grid())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,295 @@
+//! How opponents fight back. Each enemy attacks on a timer, and every attack
+//! is announced with its target a few seconds before it lands so the player
+//! has a chance to react.
+
+use super::gameboard::{Gameboard, Point};
+use super::generator::Random;
+use std::collections::HashSet;
+
+/// The enemies a battle can be fought against, by portrait.
+pub const ENEMIES: [&str; 3] = ["Enemy_1.png", "Enemy_2.png", "Enemy_3.png"];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Attack {
+    /// Takes out one of the player's correct entries.
+    Erase,
+    /// Stops a cell from being changed for this many seconds.
+    Lock(f32),
+    /// Hides the digits in a region for this many seconds.
+    Blind(f32),
+    /// Adds this many seconds to the time spent, which in countdown mode
+    /// takes them off the time left.
+    CutClock(f32),
+}
+
+impl Attack {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Attack::Erase => "Erase",
+            Attack::Lock(_) => "Lock",
+            Attack::Blind(_) => "Blind",
+            Attack::CutClock(_) => "Time Cut",
+        }
+    }
+}
+
+/// How an enemy fights.
+#[derive(Debug, Clone)]
+pub struct Behavior {
+    /// Seconds between one attack landing and the next being announced.
+    pub interval: f32,
+    /// Seconds an attack is announced for before it lands.
+    pub warning: f32,
+    /// Damage every attack does to the player, before defense.
+    pub attack: u32,
+    /// Used one after another, starting over at the end.
+    pub attacks: Vec<Attack>,
+}
+
+impl Behavior {
+    /// The behavior of the enemy with this portrait.
+    pub fn of(image_asset: &str) -> Self {
+        match image_asset {
+            "Enemy_1.png" => Behavior {
+                interval: 25.0,
+                warning: 3.0,
+                attack: 10,
+                attacks: vec![Attack::Erase],
+            },
+            "Enemy_2.png" => Behavior {
+                interval: 20.0,
+                warning: 3.0,
+                attack: 8,
+                attacks: vec![Attack::Lock(12.0), Attack::CutClock(15.0)],
+            },
+            _ => Behavior {
+                interval: 18.0,
+                warning: 4.0,
+                attack: 12,
+                attacks: vec![Attack::Blind(8.0), Attack::Erase, Attack::Lock(8.0)],
+            },
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Target {
+    Cell(Point),
+    Region(u8),
+    Clock,
+}
+
+/// An attack that has been announced but hasn't landed yet.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Telegraph {
+    pub attack: Attack,
+    pub target: Target,
+    /// Seconds until it lands.
+    pub left: f32,
+}
+
+/// What the opponent is doing to the board right now, for drawing it and
+/// for keeping the player out of locked cells.
+#[derive(Debug, Clone, Default)]
+pub struct Effects {
+    pub warned: HashSet<Point>,
+    pub locked: HashSet<Point>,
+    pub hidden: HashSet<Point>,
+}
+
+/// Keeps time for an opponent's attacks. None of this is saved, so a resumed
+/// game starts the opponent's timer over.
+#[derive(Debug, Clone)]
+pub struct Tactics {
+    pub behavior: Behavior,
+    random: Random,
+    next_attack: usize,
+    until_next: f32,
+    pub telegraph: Option<Telegraph>,
+    /// Cells and regions under an attack that lasts, with seconds left.
+    locks: Vec<(Point, f32)>,
+    blinds: Vec<(u8, f32)>,
+}
+
+impl Tactics {
+    pub fn new(behavior: Behavior, seed: u32) -> Self {
+        Tactics {
+            until_next: behavior.interval,
+            behavior,
+            random: Random::new(u64::from(seed)),
+            next_attack: 0,
+            telegraph: None,
+            locks: vec![],
+            blinds: vec![],
+        }
+    }
+
+    /// Moves the opponent on by `delta` seconds. Returns the attack that
+    /// landed, if one did, so its damage and anything else beyond the board
+    /// can be dealt with.
+    pub fn update(&mut self, delta: f32, board: &mut Gameboard) -> Option<Attack> {
+        for lock in &mut self.locks {
+            lock.1 -= delta;
+        }
+        self.locks.retain(|&(_, left)| left > 0.0);
+        for blind in &mut self.blinds {
+            blind.1 -= delta;
+        }
+        self.blinds.retain(|&(_, left)| left > 0.0);
+
+        if let Some(mut telegraph) = self.telegraph.take() {
+            telegraph.left -= delta;
+            if telegraph.left > 0.0 {
+                self.telegraph = Some(telegraph);
+                return None;
+            }
+            self.until_next = self.behavior.interval;
+            return self.land(telegraph, board);
+        }
+
+        self.until_next -= delta;
+        if self.until_next > 0.0 || self.behavior.attacks.is_empty() {
+            return None;
+        }
+        let attack = self.behavior.attacks[self.next_attack % self.behavior.attacks.len()];
+        self.next_attack += 1;
+        match self.aim(attack, board) {
+            Some(target) => {
+                self.telegraph = Some(Telegraph {
+                    attack,
+                    target,
+                    left: self.behavior.warning,
+                })
+            }
+            // Nothing to aim at, so wait for the next one
+            None => self.until_next = self.behavior.interval,
+        }
+        None
+    }
+
+    fn aim(&mut self, attack: Attack, board: &Gameboard) -> Option<Target> {
+        let mutable = board
+            .points()
+            .into_iter()
+            .filter(|&point| board.is_mutable(point));
+        let cells: Vec<Point> = match attack {
+            Attack::Erase => mutable
+                .filter(|&point| board.current[point] == board.solution[point])
+                .collect(),
+            Attack::Lock(_) => mutable
+                .filter(|&point| board.current[point].is_none())
+                .collect(),
+            Attack::Blind(_) => {
+                let dimension = board.order() * board.order();
+                return Some(Target::Region(self.random.below(dimension as usize) as u8));
+            }
+            Attack::CutClock(_) => return Some(Target::Clock),
+        };
+        if cells.is_empty() {
+            None
+        } else {
+            Some(Target::Cell(cells[self.random.below(cells.len())]))
+        }
+    }
+
+    fn land(&mut self, telegraph: Telegraph, board: &mut Gameboard) -> Option<Attack> {
+        match (telegraph.attack, telegraph.target) {
+            (Attack::Erase, Target::Cell(point)) => {
+                // The player may have changed the cell since it was aimed at
+                if board.current[point] != board.solution[point] {
+                    return None;
+                }
+                board.clear(point);
+            }
+            (Attack::Lock(seconds), Target::Cell(point)) => self.locks.push((point, seconds)),
+            (Attack::Blind(seconds), Target::Region(region)) => self.blinds.push((region, seconds)),
+            _ => {}
+        }
+        Some(telegraph.attack)
+    }
+
+    pub fn is_locked(&self, point: Point) -> bool {
+        self.locks.iter().any(|&(locked, _)| locked == point)
+    }
+
+    pub fn effects(&self, board: &Gameboard) -> Effects {
+        let regions = board.rules.regions();
+        let warned = match self.telegraph.map(|telegraph| telegraph.target) {
+            Some(Target::Cell(point)) => vec![point],
+            Some(Target::Region(region)) => regions.region(region),
+            _ => vec![],
+        };
+        Effects {
+            warned: warned.into_iter().collect(),
+            locked: self.locks.iter().map(|&(point, _)| point).collect(),
+            hidden: self
+                .blinds
+                .iter()
+                .flat_map(|&(region, _)| regions.region(region))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::grid::Grid;
+    use super::*;
+    use sudoku::Element;
+
+    const PUZZLE: [u8; 16] = [1, 2, 0, 4, 0, 0, 1, 2, 0, 1, 4, 3, 4, 3, 2, 0];
+
+    fn board() -> Gameboard {
+        let problem = Grid::from_values(2, &PUZZLE).unwrap();
+        Gameboard::import(problem, None, vec![]).unwrap()
+    }
+
+    fn tactics(attack: Attack) -> Tactics {
+        let behavior = Behavior {
+            interval: 1.0,
+            warning: 2.0,
+            attack: 5,
+            attacks: vec![attack],
+        };
+        Tactics::new(behavior, 1)
+    }
+
+    #[test]
+    fn warns_of_a_lock_before_it_lands() {
+        let mut board = board();
+        let mut tactics = tactics(Attack::Lock(3.0));
+        assert_eq!(tactics.update(0.5, &mut board), None);
+        assert!(tactics.telegraph.is_none());
+        assert_eq!(tactics.update(0.5, &mut board), None);
+        let point = match tactics.telegraph.map(|telegraph| telegraph.target) {
+            Some(Target::Cell(point)) => point,
+            other => panic!("expected a cell to be aimed at, got {:?}", other),
+        };
+        assert_eq!(board.current[point], None);
+        assert!(tactics.effects(&board).warned.contains(&point));
+        assert!(!tactics.is_locked(point));
+
+        assert_eq!(tactics.update(1.0, &mut board), None);
+        assert_eq!(tactics.update(1.0, &mut board), Some(Attack::Lock(3.0)));
+        assert!(tactics.is_locked(point));
+        tactics.update(3.0, &mut board);
+        assert!(!tactics.is_locked(point));
+    }
+
+    #[test]
+    fn erases_only_what_is_still_correct() {
+        let mut board = board();
+        let mut tactics = tactics(Attack::Erase);
+        let correct = Point(2, 0);
+        board.insert(correct, Element(3));
+        tactics.update(1.0, &mut board);
+        assert_eq!(tactics.update(2.0, &mut board), Some(Attack::Erase));
+        assert_eq!(board.current[correct], None);
+
+        board.insert(correct, Element(3));
+        tactics.update(1.0, &mut board);
+        board.insert(correct, Element(4));
+        assert_eq!(tactics.update(2.0, &mut board), None);
+        assert_eq!(board.current[correct], Some(Element(4)));
+    }
+}
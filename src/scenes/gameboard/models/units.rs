@@ -0,0 +1,42 @@
+//! Helpers for the groups of cells that must each hold distinct values.
+//! Which of them apply to a board is up to its rule set.
+
+use super::gameboard::Point;
+
+pub fn row(y: u8, order: u8) -> Vec<Point> {
+    (0..order * order).map(|x| Point(x, y)).collect()
+}
+
+pub fn column(x: u8, order: u8) -> Vec<Point> {
+    (0..order * order).map(|y| Point(x, y)).collect()
+}
+
+/// The two main diagonals, top-left to bottom-right first.
+pub fn diagonals(order: u8) -> Vec<Vec<Point>> {
+    let dimension = order * order;
+    vec![
+        (0..dimension).map(|i| Point(i, i)).collect(),
+        (0..dimension)
+            .map(|i| Point(dimension - 1 - i, i))
+            .collect(),
+    ]
+}
+
+/// The cells directly above, below and to either side of `point`.
+pub fn neighbours(point: Point, dimension: u8) -> Vec<Point> {
+    let Point(x, y) = point;
+    let mut points = vec![];
+    if x > 0 {
+        points.push(Point(x - 1, y));
+    }
+    if x + 1 < dimension {
+        points.push(Point(x + 1, y));
+    }
+    if y > 0 {
+        points.push(Point(x, y - 1));
+    }
+    if y + 1 < dimension {
+        points.push(Point(x, y + 1));
+    }
+    points
+}
@@ -2,55 +2,191 @@ use ggez::graphics::Point2;
 use ggez::*;
 use ggez_goodies::scene;
 use specs;
+use warmy;
+use DESIRED_FPS;
 
 use super::models::*;
+use super::sounds::{Sound, Sounds};
 use super::views::*;
+use common::resources;
 use input;
 use scenes::*;
 use world::World;
 
+/// How often, in seconds, the game is saved while the board is open.
+const AUTOSAVE_INTERVAL: f64 = 30.0;
+const OPPONENT_DEFENSE: u32 = 2;
+/// The share of the empty cells the player has to fill in correctly to
+/// defeat the opponent.
+const OPPONENT_HP_SHARE: f32 = 0.8;
+
 pub struct GameboardScene {
     // Models
     gameboard: Gameboard,
     character: Character,
     opponent: Character,
+    tactics: Tactics,
+    /// The ability definitions as warmy last loaded them, and as they were
+    /// when the player's abilities were last built from them.
+    ability_definitions: warmy::Res<resources::Ron<Vec<AbilityDefinition>>>,
+    built_definitions: Vec<AbilityDefinition>,
+
+    // Seconds spent on the board, and since the last autosave
+    elapsed: f64,
+    since_autosave: f64,
+    /// In countdown mode, the seconds the player has to solve the board.
+    time_limit: Option<f64>,
+
+    // Whether a notes modifier key is currently held
+    notes_held: bool,
+    // Whether to pause on the next update
+    pause_requested: bool,
+    /// The slot of an ability waiting for the player to pick its target,
+    /// the shape it is being aimed in and the cell it would be aimed at.
+    targeting: Option<usize>,
+    aim_shape: Targeting,
+    hovered_cell: Option<Point>,
 
     // Views
     background_view: BackgroundView,
     gameboard_view: GameboardView,
     abilities_view: AbilitiesView,
     timer_view: TimerView,
+    hint_view: HintView,
+    rating_view: LabelView,
+    attack_view: LabelView,
     character_portrait_view: PortraitView,
     opponent_portrait_view: PortraitView,
 
+    sounds: Sounds,
+
     // Component dispatcher
     dispatcher: specs::Dispatcher<'static, 'static>,
 }
 
 impl GameboardScene {
-    pub fn new(ctx: &mut Context, world: &mut World) -> Self {
-        use sudoku::Difficulty;
+    pub fn new(
+        order: u8,
+        variant: Variant,
+        rules: &[Rule],
+        rating: Rating,
+        ctx: &mut Context,
+        world: &mut World,
+    ) -> Self {
+        Self::with_seed(fresh_seed(), order, variant, rules, rating, ctx, world)
+    }
+
+    pub fn with_seed(
+        seed: u32,
+        order: u8,
+        variant: Variant,
+        rules: &[Rule],
+        rating: Rating,
+        ctx: &mut Context,
+        world: &mut World,
+    ) -> Self {
+        let gameboard = Gameboard::with_seed(seed, order, variant, rules, rating);
+        Self::with_board(gameboard, ctx, world)
+    }
+
+    pub fn with_board(gameboard: Gameboard, ctx: &mut Context, world: &mut World) -> Self {
+        let character = Self::abilities(ctx, world).into_iter().fold(
+            Character::new("Main", CharacterKind::Character, "Player_2.png", ctx, world),
+            Character::add_ability,
+        );
+        let empty_cells = gameboard
+            .points()
+            .into_iter()
+            .filter(|&point| gameboard.current[point].is_none())
+            .count();
+        let damage = Stats::new(0, OPPONENT_DEFENSE).damage(PLACEMENT_ATTACK);
+        let opponent_hp = (empty_cells as f32 * damage as f32 * OPPONENT_HP_SHARE).ceil() as u32;
+        let seed = gameboard.seed.unwrap_or_else(fresh_seed);
+        let enemy = ENEMIES[seed as usize % ENEMIES.len()];
+        let opponent = Character::new("Opponent", CharacterKind::Opponent, enemy, ctx, world)
+            .with_stats(opponent_hp.max(1), OPPONENT_DEFENSE);
+        Self::with_models(gameboard, character, opponent, 0.0, ctx, world)
+    }
+
+    /// Picks up the game from the last save, if there is one that can be read.
+    pub fn resume(ctx: &mut Context, world: &mut World) -> Option<Self> {
+        if !SaveGame::exists(&world.user_data_dir) {
+            return None;
+        }
+        let save = match SaveGame::load(&world.user_data_dir) {
+            Ok(save) => save,
+            Err(e) => {
+                warn!("Could not load saved game: {}", e);
+                return None;
+            }
+        };
+        let gameboard = match Gameboard::restore(save.board) {
+            Ok(gameboard) => gameboard,
+            Err(e) => {
+                warn!("Could not restore saved board: {}", e);
+                return None;
+            }
+        };
+        let abilities = Self::abilities(ctx, world);
+        let character = Character::restore(&save.character, abilities, ctx, world);
+        let opponent = Character::restore(&save.opponent, vec![], ctx, world);
+        let scene = Self::with_models(gameboard, character, opponent, save.elapsed, ctx, world);
+        Some(scene.with_time_limit(save.time_limit))
+    }
+
+    /// The player's abilities, in slot order.
+    fn abilities(ctx: &mut Context, world: &mut World) -> Vec<Ability> {
+        let definitions = ability_definitions(ctx, world).borrow().0.clone();
+        definitions
+            .iter()
+            .map(|definition| Ability::new(definition, ctx, world))
+            .collect()
+    }
+
+    /// Rebuilds the player's abilities if the definitions file has changed
+    /// since they were built.
+    fn reload_abilities(&mut self, ctx: &mut Context, world: &mut World) {
+        let definitions = self.ability_definitions.borrow().0.clone();
+        if definitions == self.built_definitions {
+            return;
+        }
+        info!("Reloading ability definitions");
+        let abilities = definitions
+            .iter()
+            .map(|definition| Ability::new(definition, ctx, world))
+            .collect();
+        self.character.replace_abilities(abilities);
+        self.built_definitions = definitions;
+        // The ability being aimed may have moved or gone
+        self.targeting = None;
+    }
+
+    fn with_models(
+        gameboard: Gameboard,
+        character: Character,
+        opponent: Character,
+        elapsed: f64,
+        ctx: &mut Context,
+        world: &mut World,
+    ) -> Self {
+        let tactics = Tactics::new(Behavior::of(&opponent.image_asset), fresh_seed());
+        let ability_definitions = ability_definitions(ctx, world);
+        let built_definitions = ability_definitions.borrow().0.clone();
         GameboardScene {
-            gameboard: Gameboard::new(Difficulty::Advanced),
-            character: Character::new(
-                "Main",
-                CharacterKind::Character,
-                "Player_2.png",
-                ctx,
-                world,
-            ).add_ability(Ability::new(
-                "Reveal Numbers",
-                "Badge1",
-                ctx,
-                world,
-            )),
-            opponent: Character::new(
-                "Opponent",
-                CharacterKind::Opponent,
-                "Enemy_3.png",
-                ctx,
-                world,
-            ),
+            gameboard,
+            character,
+            opponent,
+            tactics,
+            ability_definitions,
+            built_definitions,
+            elapsed,
+            since_autosave: 0.0,
+            time_limit: None,
+            notes_held: false,
+            pause_requested: false,
+            targeting: None,
+            aim_shape: Targeting::None,
+            hovered_cell: None,
 
             background_view: BackgroundView::new(
                 BackgroundViewSettings::new("area-1.png", ctx, world).unwrap(),
@@ -60,6 +196,19 @@ impl GameboardScene {
             ),
             abilities_view: AbilitiesView::new(AbilitiesViewSettings::new(ctx, world)),
             timer_view: TimerView::new(TimerViewSettings::new(ctx, world)),
+            hint_view: HintView::new(HintViewSettings::new(ctx, world)),
+            rating_view: LabelView::new(LabelViewSettings::new(
+                Point2::new(55.0, 40.0),
+                20,
+                ctx,
+                world,
+            )),
+            attack_view: LabelView::new(LabelViewSettings::new(
+                Point2::new(500.0, 545.0),
+                20,
+                ctx,
+                world,
+            )),
             character_portrait_view: PortraitView::new(PortraitViewSettings::new(
                 CharacterKind::Character,
                 Point2::new(500.0, 75.0),
@@ -73,33 +222,241 @@ impl GameboardScene {
                 world,
             )),
 
+            sounds: Sounds::new(ctx, world),
+
             dispatcher: Self::register_systems(),
         }
     }
 
+    /// Ends the battle after `limit` mistakes, or never if `None`.
+    pub fn with_mistake_limit(mut self, limit: Option<usize>) -> Self {
+        self.gameboard.mistake_limit = limit;
+        self
+    }
+
+    /// Counts the clock down from `limit` seconds, losing the battle when it
+    /// runs out, or counts up forever if `None`.
+    pub fn with_time_limit(mut self, limit: Option<f64>) -> Self {
+        self.time_limit = limit;
+        self
+    }
+
+    fn is_out_of_time(&self) -> bool {
+        self.time_limit.map_or(false, |limit| self.elapsed >= limit)
+    }
+
+    /// The whole seconds shown on the clock: time left in countdown mode,
+    /// otherwise time spent.
+    fn clock(&self) -> u64 {
+        match self.time_limit {
+            Some(limit) => (limit - self.elapsed).max(0.0).ceil() as u64,
+            None => self.elapsed as u64,
+        }
+    }
+
+    /// Acts on whatever the player picked from the pause menu.
+    fn unpause(&mut self, choice: PauseChoice, world: &World) -> FSceneSwitch {
+        match choice {
+            PauseChoice::Restart => {
+                self.gameboard.restart();
+                self.character.heal();
+                self.opponent.heal();
+                self.tactics = Tactics::new(self.tactics.behavior.clone(), fresh_seed());
+                self.elapsed = 0.0;
+                self.since_autosave = 0.0;
+                self.save(world);
+                scene::SceneSwitch::None
+            }
+            PauseChoice::Quit => {
+                self.save(world);
+                scene::SceneSwitch::Pop
+            }
+            PauseChoice::Resume | PauseChoice::Settings => scene::SceneSwitch::None,
+        }
+    }
+
+    fn outcome(&self) -> Option<Outcome> {
+        if self.gameboard.is_solved() {
+            Some(Outcome::Solved)
+        } else if self.opponent.is_defeated() {
+            Some(Outcome::OpponentDefeated)
+        } else if self.character.is_defeated() {
+            Some(Outcome::Defeated)
+        } else if self.gameboard.is_lost() {
+            Some(Outcome::OutOfMistakes)
+        } else if self.is_out_of_time() {
+            Some(Outcome::OutOfTime)
+        } else {
+            None
+        }
+    }
+
+    /// Clears the save, since there's nothing left to continue, and records
+    /// the battle in the player's statistics.
+    fn finish(&self, outcome: Outcome, world: &mut World) -> BattleReport {
+        if let Err(e) = SaveGame::delete(&world.user_data_dir) {
+            warn!("Could not remove saved game: {}", e);
+        }
+        let won = outcome.is_win();
+        let statistics = &mut world.statistics;
+        let is_best_time = statistics.record(self.gameboard.rating, won, self.elapsed);
+        if let Err(e) = statistics.write(&world.user_data_dir) {
+            warn!("Could not save statistics: {}", e);
+        }
+
+        let mut rewards = vec![];
+        if won && is_best_time {
+            rewards.push("New best time!".to_string());
+        }
+        if won && self.gameboard.mistakes == 0 {
+            rewards.push("Flawless: no mistakes".to_string());
+        }
+        if won && self.gameboard.hints_used == 0 {
+            rewards.push("Unaided: no hints".to_string());
+        }
+        BattleReport {
+            outcome,
+            elapsed: self.elapsed,
+            gameboard: self.gameboard.clone(),
+            rewards,
+        }
+    }
+
     fn register_systems() -> specs::Dispatcher<'static, 'static> {
         specs::DispatcherBuilder::new().build()
     }
+
+    fn save(&self, world: &World) {
+        let save = SaveGame {
+            version: SAVE_VERSION,
+            board: self.gameboard.snapshot(),
+            elapsed: self.elapsed,
+            time_limit: self.time_limit,
+            character: self.character.snapshot(),
+            opponent: self.opponent.snapshot(),
+        };
+        match save.write(&world.user_data_dir) {
+            Ok(()) => debug!("Saved game to {:?}", world.user_data_dir),
+            Err(e) => warn!("Could not save game: {}", e),
+        }
+    }
+
+    fn export(&self, world: &World) {
+        match export::export_all(&self.gameboard, &world.user_data_dir) {
+            Ok(dir) => info!("Exported board to {:?}", dir),
+            Err(e) => warn!("Could not export board: {}", e),
+        }
+    }
 }
 
 impl scene::Scene<World, input::InputEvent> for GameboardScene {
     fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        self.sounds.enabled = gameworld.settings.sound;
+        self.gameboard_view.settings.show_conflicts = gameworld.settings.show_conflicts;
+        if let Some(choice) = gameworld.pause_choice.take() {
+            if let scene::SceneSwitch::Pop = self.unpause(choice, gameworld) {
+                return scene::SceneSwitch::Pop;
+            }
+        }
+        if self.pause_requested {
+            self.pause_requested = false;
+            return scene::SceneSwitch::Push(Box::new(PauseScene::new()));
+        }
+
         self.dispatcher.dispatch(&gameworld.specs_world.res);
-        if self.gameboard.is_solved() {
-            println!("solved!");
-            scene::SceneSwitch::Pop
+
+        let delta = 1.0 / f64::from(DESIRED_FPS);
+        let frozen = self.is_time_warped();
+        if !frozen {
+            self.elapsed += delta;
+        }
+        self.since_autosave += delta;
+        if self.since_autosave >= AUTOSAVE_INTERVAL {
+            self.since_autosave = 0.0;
+            self.save(gameworld);
+        }
+        self.gameboard_view.update(delta as f32);
+        for ability in &mut self.character.abilities {
+            ability.update(delta as f32);
+        }
+        let attack = if frozen {
+            None
         } else {
-            scene::SceneSwitch::None
+            self.tactics.update(delta as f32, &mut self.gameboard)
+        };
+        if let Some(attack) = attack {
+            self.character.take_hit(self.tactics.behavior.attack);
+            if let Attack::CutClock(seconds) = attack {
+                self.elapsed += f64::from(seconds);
+            }
+            self.sounds.play(Sound::Wrong);
+        }
+
+        match self.outcome() {
+            Some(outcome) => {
+                let report = self.finish(outcome, gameworld);
+                scene::SceneSwitch::Replace(Box::new(ResultScene::new(report)))
+            }
+            None => scene::SceneSwitch::None,
         }
     }
 
-    fn draw(&mut self, _gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.reload_abilities(ctx, gameworld);
         self.background_view.draw(ctx)?;
         self.character_portrait_view.draw(ctx, &self.character)?;
         self.opponent_portrait_view.draw(ctx, &self.opponent)?;
-        self.abilities_view.draw(ctx, &self.character.abilities)?;
-        self.gameboard_view.draw(ctx, &self.gameboard)?;
-        self.timer_view.draw(ctx, 0)?;
+        self.abilities_view.draw(ctx, &self.character)?;
+        let effects = self.tactics.effects(&self.gameboard);
+        self.gameboard_view.draw(ctx, &self.gameboard, &effects)?;
+        if let (Some(_), Some(point)) = (self.targeting, self.hovered_cell) {
+            let area = self.aim_shape.area(&self.gameboard, point);
+            self.gameboard_view
+                .draw_highlight(ctx, &self.gameboard, &area)?;
+        }
+        let clock = self.clock();
+        let frozen = self.is_time_warped();
+        self.timer_view.draw(ctx, clock, frozen)?;
+        self.hint_view.draw(ctx, &self.gameboard)?;
+        let variant = self.gameboard.variant();
+        let mut names: Vec<&str> = self
+            .gameboard
+            .rules
+            .rules()
+            .iter()
+            .map(|rule| rule.name())
+            .collect();
+        if variant != Variant::Classic {
+            names.insert(0, variant.name());
+        }
+        let mut label = names.join(" ");
+        if self.gameboard.is_rated() {
+            let rating = format!("Rating: {}", self.gameboard.rating.name());
+            label = if label.is_empty() {
+                rating
+            } else {
+                format!("{}  {}", label, rating)
+            };
+        }
+        if let Some(seed) = self.gameboard.seed {
+            label.push_str(&format!("  Seed: {}", seed));
+        }
+        match self.gameboard.mistake_limit {
+            Some(limit) => label.push_str(&format!(
+                "  Mistakes: {}/{}",
+                self.gameboard.mistakes, limit
+            )),
+            None if self.gameboard.mistakes > 0 => {
+                label.push_str(&format!("  Mistakes: {}", self.gameboard.mistakes))
+            }
+            None => {}
+        }
+        self.rating_view.draw(ctx, &label)?;
+        if let Some(telegraph) = self.tactics.telegraph {
+            let left = telegraph.left.ceil() as u32;
+            let warning = format!("{} in {}", telegraph.attack.name(), left);
+            self.attack_view.draw(ctx, &warning)?;
+        }
         Ok(())
     }
 
@@ -107,58 +464,322 @@ impl scene::Scene<World, input::InputEvent> for GameboardScene {
         "Game Board"
     }
 
-    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
-        use input::{events::InputEffect, Button};
-
-        match (ev, self.gameboard.selected_cell) {
-            (InputEffect::Axis(axis, is_positive), _) if !started => {
+    fn input(&mut self, gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        if let Some(slot) = self.targeting {
+            self.aim(gameworld, slot, ev, started);
+            return;
+        }
+        match command(ev, self.gameboard.selected_cell, started) {
+            Some(Command::MoveSelection(axis, is_positive)) => {
                 self.gameboard.move_selected_cell(axis, is_positive)
             }
-            (InputEffect::Button(button, None), Some(point)) => match button {
-                Button::Delete => {
-                    if self.gameboard.is_mutable(point) {
-                        let _ = self.gameboard.remove(point);
-                    }
+            Some(Command::Undo) => {
+                let tactics = &self.tactics;
+                if let Some(point) = self.gameboard.undo(|point| tactics.is_locked(point)) {
+                    self.gameboard.selected_cell = Some(point);
                 }
-                Button::Num1 => self.assign_number(point, 1),
-                Button::Num2 => self.assign_number(point, 2),
-                Button::Num3 => self.assign_number(point, 3),
-                Button::Num4 => self.assign_number(point, 4),
-                Button::Num5 => self.assign_number(point, 5),
-                Button::Num6 => self.assign_number(point, 6),
-                Button::Num7 => self.assign_number(point, 7),
-                Button::Num8 => self.assign_number(point, 8),
-                Button::Num9 => self.assign_number(point, 9),
-                _ => {}
+            }
+            Some(Command::Redo) => {
+                let tactics = &self.tactics;
+                if let Some(point) = self.gameboard.redo(|point| tactics.is_locked(point)) {
+                    self.gameboard.selected_cell = Some(point);
+                }
+            }
+            Some(Command::Pause) => {
+                self.save(gameworld);
+                self.pause_requested = true;
+            }
+            Some(Command::Hint) => self.gameboard.request_hint(),
+            Some(Command::Export) => self.export(gameworld),
+            Some(Command::HoldNotes(held)) => self.notes_held = held,
+            Some(Command::ToggleNotes) => self.gameboard.notes_mode = !self.gameboard.notes_mode,
+            Some(Command::Delete(point)) => {
+                if self.gameboard.is_mutable(point) && !self.tactics.is_locked(point) {
+                    let _ = self.gameboard.remove(point);
+                }
+            }
+            Some(Command::Enter(point, num)) => {
+                let dimension = self.gameboard.order() * self.gameboard.order();
+                if num <= dimension {
+                    self.enter_number(point, num);
+                }
+            }
+            Some(Command::UseAbility(slot)) => self.use_ability(slot),
+            Some(Command::Click(x, y, started)) => match self.abilities_view.slot_at(x, y) {
+                Some(slot) if started => self.use_ability(slot),
+                Some(_) => {}
+                None => self.handle_mouse(x, y),
             },
-            (InputEffect::Button(Button::Select, Some((x, y))), _) => self.handle_mouse(x, y),
-            (_, _) => {}
+            None => {}
         }
     }
 }
 
+/// What an input asks of the board, before it is checked against the state
+/// of the game.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Command {
+    MoveSelection(input::Axis, bool),
+    Undo,
+    Redo,
+    Pause,
+    Hint,
+    Export,
+    HoldNotes(bool),
+    ToggleNotes,
+    Delete(Point),
+    Enter(Point, u8),
+    UseAbility(usize),
+    /// A mouse press or release, and whether it was a press.
+    Click(i32, i32, bool),
+}
+
+/// The command for `ev` while `selected_cell` is selected. Number and delete
+/// keys only mean something with a cell selected.
+fn command(ev: input::InputEvent, selected_cell: Option<Point>, started: bool) -> Option<Command> {
+    use input::{events::InputEffect, Button};
+
+    match (ev, selected_cell) {
+        (InputEffect::Axis(axis, is_positive), _) if !started => {
+            Some(Command::MoveSelection(axis, is_positive))
+        }
+        (InputEffect::Button(Button::Undo, _), _) if started => Some(Command::Undo),
+        (InputEffect::Button(Button::Redo, _), _) if started => Some(Command::Redo),
+        (InputEffect::Button(Button::Exit, _), _) | (InputEffect::Button(Button::Pause, _), _)
+            if started =>
+        {
+            Some(Command::Pause)
+        }
+        (InputEffect::Button(Button::Hint, _), _) if started => Some(Command::Hint),
+        (InputEffect::Button(Button::Export, _), _) if started => Some(Command::Export),
+        (InputEffect::Button(Button::Notes, _), _) => Some(Command::HoldNotes(started)),
+        (InputEffect::Button(Button::ToggleNotes, _), _) if started => Some(Command::ToggleNotes),
+        (InputEffect::Button(Button::Delete, None), Some(point)) if started => {
+            Some(Command::Delete(point))
+        }
+        (InputEffect::Button(button, None), Some(point))
+            if started && button.number().is_some() =>
+        {
+            button.number().map(|num| Command::Enter(point, num))
+        }
+        (InputEffect::Button(button, _), _) if started && button.ability().is_some() => {
+            button.ability().map(Command::UseAbility)
+        }
+        (InputEffect::Button(Button::Select, Some((x, y))), _) => {
+            Some(Command::Click(x, y, started))
+        }
+        (_, _) => None,
+    }
+}
+
 impl GameboardScene {
+    /// Holding a notes key flips whichever mode the board is currently in.
+    /// Nothing can be entered in a cell the opponent has locked.
+    fn enter_number(&mut self, point: Point, num: u8) {
+        use sudoku::Element;
+
+        if self.tactics.is_locked(point) {
+            return;
+        }
+
+        if self.gameboard.notes_mode != self.notes_held {
+            self.gameboard.toggle_note(point, Element(num))
+        } else {
+            self.assign_number(point, num)
+        }
+    }
+
     fn assign_number(&mut self, point: Point, num: u8) {
         use sudoku::Element;
 
-        if self.gameboard.is_mutable(point) {
-            self.gameboard.insert(point, Element(num))
+        if !self.gameboard.is_mutable(point) {
+            return;
+        }
+        let value = Element(num);
+        let is_correct = self.gameboard.insertion_is_correct(point, value);
+        self.gameboard.insert(point, value);
+        if is_correct {
+            if self.gameboard.score(point) {
+                self.opponent.take_hit(PLACEMENT_ATTACK);
+                let completed = self.gameboard.completed_units(point) as u32;
+                self.character
+                    .gain_energy(PLACEMENT_ENERGY + completed * COMPLETION_ENERGY);
+            }
+            self.sounds.play(Sound::Correct);
+        } else {
+            self.gameboard.record_mistake();
+            self.character.take_hit(MISTAKE_ATTACK);
+            let color = self.gameboard_view.settings.mistake_flash_color;
+            self.gameboard_view.flash(point, color);
+            self.sounds.play(Sound::Wrong);
+        }
+    }
+
+    /// Whether a Time Warp is holding the clock and the opponent still.
+    fn is_time_warped(&self) -> bool {
+        self.character
+            .abilities
+            .iter()
+            .any(|ability| ability.is_active() && ability.effect == Effect::TimeWarp)
+    }
+
+    /// Abilities that need a target wait for the player to pick one on the
+    /// board before they are used.
+    fn use_ability(&mut self, slot: usize) {
+        match self.character.abilities.get(slot) {
+            Some(ability) if !self.character.can_use(ability) => {}
+            Some(ability) if ability.targeting != Targeting::None => {
+                self.targeting = Some(slot);
+                self.aim_shape = ability.targeting.shapes()[0];
+                self.hovered_cell = self.gameboard.selected_cell;
+            }
+            Some(_) => self.activate_ability(slot, &[]),
+            None => {}
+        }
+    }
+
+    /// Input while picking a target for the ability in `slot`. Anything that
+    /// isn't aiming, confirming or cancelling is ignored until it's done.
+    fn aim(&mut self, gameworld: &World, slot: usize, ev: input::InputEvent, started: bool) {
+        use input::{events::InputEffect, Button};
+
+        match ev {
+            InputEffect::MouseMotion(x, y, _, _) => self.hovered_cell = self.cell_at(x, y),
+            InputEffect::Axis(axis, is_positive) if !started => {
+                self.gameboard.move_selected_cell(axis, is_positive);
+                self.hovered_cell = self.gameboard.selected_cell;
+            }
+            InputEffect::Button(Button::Select, Some((x, y))) if started => {
+                match (self.cell_at(x, y), self.abilities_view.slot_at(x, y)) {
+                    (Some(point), _) => self.confirm_target(slot, point),
+                    (None, Some(other)) => self.switch_targeting(slot, other),
+                    (None, None) => {}
+                }
+            }
+            InputEffect::Button(Button::Select, None) if started => {
+                if let Some(point) = self.hovered_cell {
+                    self.confirm_target(slot, point);
+                }
+            }
+            InputEffect::Button(Button::Rotate, _) if started => {
+                let shapes = self.character.abilities[slot].targeting.shapes();
+                let next = shapes
+                    .iter()
+                    .position(|&shape| shape == self.aim_shape)
+                    .map_or(0, |i| (i + 1) % shapes.len());
+                self.aim_shape = shapes[next];
+            }
+            InputEffect::Button(Button::Exit, _) if started => self.targeting = None,
+            InputEffect::Button(Button::Pause, _) if started => {
+                self.targeting = None;
+                self.save(gameworld);
+                self.pause_requested = true;
+            }
+            InputEffect::Button(button, _) if started => {
+                if let Some(other) = button.ability() {
+                    self.switch_targeting(slot, other);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Picking the ability being aimed again cancels it, while picking
+    /// another one starts using that instead.
+    fn switch_targeting(&mut self, slot: usize, other: usize) {
+        self.targeting = None;
+        if other != slot {
+            self.use_ability(other);
+        }
+    }
+
+    fn confirm_target(&mut self, slot: usize, point: Point) {
+        self.targeting = None;
+        let area = self.aim_shape.area(&self.gameboard, point);
+        self.activate_ability(slot, &area);
+    }
+
+    /// Uses the ability in `slot` on the cells in `area`, which is empty for
+    /// abilities that don't need a target.
+    fn activate_ability(&mut self, slot: usize, area: &[Point]) {
+        if !self.character.activate_ability(slot) {
+            return;
+        }
+        let effect = self.character.abilities[slot].effect.clone();
+        let duration = self.character.abilities[slot].duration;
+        match effect {
+            Effect::RevealNumbers(count) => {
+                let color = self.gameboard_view.settings.reveal_flash_color;
+                let tactics = &self.tactics;
+                let revealed = self
+                    .gameboard
+                    .reveal(count, fresh_seed(), |point| tactics.is_locked(point));
+                for point in revealed {
+                    self.gameboard_view.flash(point, color);
+                }
+                self.sounds.play(Sound::Reveal);
+            }
+            Effect::CheckRegion => {
+                let color = self.gameboard_view.settings.check_flash_color;
+                let wrong = self.gameboard.wrong_entries(area);
+                for &point in &wrong {
+                    self.gameboard_view.flash_for(point, color, duration);
+                }
+                let sound = if wrong.is_empty() {
+                    Sound::Correct
+                } else {
+                    Sound::Wrong
+                };
+                self.sounds.play(sound);
+            }
+            Effect::TimeWarp => self.sounds.play(Sound::Reveal),
         }
     }
 
     fn handle_mouse(&mut self, x: i32, y: i32) {
+        self.gameboard.selected_cell = self.cell_at(x, y);
+    }
+
+    /// The cell under a point on the screen, if any.
+    fn cell_at(&self, x: i32, y: i32) -> Option<Point> {
         let x = x as f32 - self.gameboard_view.settings.position.x;
         let y = y as f32 - self.gameboard_view.settings.position.y;
-        self.gameboard.selected_cell = if x >= 0.0
+        if x >= 0.0
             && x < self.gameboard_view.settings.size
             && y >= 0.0
             && y < self.gameboard_view.settings.size
         {
-            let cell_x = (x / self.gameboard_view.settings.size * 9.0) as u8;
-            let cell_y = (y / self.gameboard_view.settings.size * 9.0) as u8;
+            let dimension = f32::from(self.gameboard.order() * self.gameboard.order());
+            let cell_x = (x / self.gameboard_view.settings.size * dimension) as u8;
+            let cell_y = (y / self.gameboard_view.settings.size * dimension) as u8;
             Some(Point(cell_x, cell_y))
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{events::InputEffect, Button};
+
+    #[test]
+    fn ability_hotkeys_work_with_a_cell_selected() {
+        let press = InputEffect::Button(Button::Ability2, None);
+        for &selected in &[None, Some(Point(4, 4))] {
+            assert_eq!(command(press, selected, true), Some(Command::UseAbility(1)));
+            assert_eq!(command(press, selected, false), None);
+        }
+    }
+
+    #[test]
+    fn numbers_need_a_selected_cell() {
+        let press = InputEffect::Button(Button::Num5, None);
+        assert_eq!(
+            command(press, Some(Point(4, 4)), true),
+            Some(Command::Enter(Point(4, 4), 5))
+        );
+        assert_eq!(command(press, None, true), None);
+    }
+}
@@ -0,0 +1,73 @@
+//! Sound effects for the board. Each one gets its own source up front, since
+//! scenes don't have a `Context` to hand when input arrives.
+
+use common::resources;
+use ggez::audio;
+use ggez::Context;
+use warmy;
+use world::World;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Sound {
+    Correct,
+    Wrong,
+    Reveal,
+}
+
+pub struct Sounds {
+    /// Follows the sound setting; nothing plays while it's off.
+    pub enabled: bool,
+    correct: Option<audio::Source>,
+    wrong: Option<audio::Source>,
+    reveal: Option<audio::Source>,
+}
+
+impl Sounds {
+    pub fn new(ctx: &mut Context, world: &mut World) -> Self {
+        Sounds {
+            enabled: world.settings.sound,
+            correct: Self::load("LowDing_Correct.mp3", ctx, world),
+            wrong: Self::load("WrongNumber_Sound.mp3", ctx, world),
+            reveal: Self::load("HighDing_Reveal.mp3", ctx, world),
+        }
+    }
+
+    /// A missing sound shouldn't stop the game, so failures are only logged.
+    fn load(sound_asset: &str, ctx: &mut Context, world: &mut World) -> Option<audio::Source> {
+        let data = world.assets.get::<_, resources::SoundData>(
+            &warmy::FSKey::new(format!("/sounds/{}", sound_asset)),
+            ctx,
+        );
+        let data = match data {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Could not read sound {}: {}", sound_asset, e);
+                return None;
+            }
+        };
+        let data = data.borrow().0.clone();
+        match audio::Source::from_data(ctx, data) {
+            Ok(source) => Some(source),
+            Err(e) => {
+                warn!("Could not load sound {}: {}", sound_asset, e);
+                None
+            }
+        }
+    }
+
+    pub fn play(&self, sound: Sound) {
+        if !self.enabled {
+            return;
+        }
+        let source = match sound {
+            Sound::Correct => &self.correct,
+            Sound::Wrong => &self.wrong,
+            Sound::Reveal => &self.reveal,
+        };
+        if let Some(ref source) = *source {
+            if let Err(e) = source.play() {
+                warn!("Could not play sound {:?}: {}", sound, e);
+            }
+        }
+    }
+}
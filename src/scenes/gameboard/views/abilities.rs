@@ -1,19 +1,28 @@
-use super::super::models::Ability;
+use super::super::models::{Ability, AbilityStatus, Character};
 use common::colors;
 use common::resources;
 use common::util::*;
-use ggez::graphics::{self, Point2, Rect};
+use ggez::graphics::{self, Color, Point2, Rect};
 use ggez::{Context, GameResult};
 use warmy;
 use world::World;
 
 const PLACEHOLDER_SIZE: f32 = 70.0;
 const LEADING_PADDING: f32 = 15.0;
+const SLOTS: usize = 3;
 
 #[derive(Debug, Clone)]
 pub struct AbilitiesViewSettings {
     pub position: Point2,
     pub size: Point2,
+    /// Tints for a badge that only has an inactive image, so it can still be
+    /// told apart when it is in stock or active.
+    pub in_stock_tint: Color,
+    pub active_tint: Color,
+    /// The energy meter runs along the bottom of the container.
+    pub energy_bar_height: f32,
+    pub energy_bar_color: Color,
+    pub energy_bar_background_color: Color,
     background: warmy::Res<resources::Image>,
 }
 
@@ -26,6 +35,11 @@ impl AbilitiesViewSettings {
         AbilitiesViewSettings {
             position: Point2::new(500.0, 215.0),
             size: Point2::new(270.0, 100.0),
+            in_stock_tint: colors::YELLOW,
+            active_tint: colors::ORANGE,
+            energy_bar_height: 6.0,
+            energy_bar_color: colors::LIGHT_BLUE,
+            energy_bar_background_color: colors::DARK_GRAY,
             background,
         }
     }
@@ -41,8 +55,26 @@ impl AbilitiesView {
         AbilitiesView { settings }
     }
 
-    pub fn draw(&self, ctx: &mut Context, abilities: &[Ability]) -> GameResult<()> {
+    /// The slot under a point on the screen, if any.
+    pub fn slot_at(&self, x: i32, y: i32) -> Option<usize> {
         let settings = &self.settings;
+        let horizontal_padding =
+            (settings.size.x - LEADING_PADDING - PLACEHOLDER_SIZE * SLOTS as f32) / SLOTS as f32;
+        let top = settings.position.y + (settings.size.y - PLACEHOLDER_SIZE) / 2.0;
+        (0..SLOTS).find(|&index| {
+            let left = settings.position.x
+                + LEADING_PADDING
+                + (horizontal_padding + PLACEHOLDER_SIZE) * index as f32;
+            Rect::new(left, top, PLACEHOLDER_SIZE, PLACEHOLDER_SIZE)
+                .contains(Point2::new(x as f32, y as f32))
+        })
+    }
+
+    /// Draws `character`'s abilities and energy. Abilities the character
+    /// can't afford show their inactive badge.
+    pub fn draw(&self, ctx: &mut Context, character: &Character) -> GameResult<()> {
+        let settings = &self.settings;
+        let abilities = &character.abilities;
 
         graphics::set_color(ctx, graphics::WHITE)?;
 
@@ -54,7 +86,14 @@ impl AbilitiesView {
         let horizontal_padding =
             (background.width() as f32 - LEADING_PADDING - sum_of_widths) / 3.0;
         for (i, ability) in abilities.iter().enumerate() {
-            self.draw_ability_at_index(ctx, Some(ability), i as u32, horizontal_padding)?;
+            // Only an ability in stock can be greyed out for want of energy
+            let affordable = !ability.is_in_stock() || ability.cost <= character.stats.energy;
+            self.draw_ability_at_index(
+                ctx,
+                Some((ability, affordable)),
+                i as u32,
+                horizontal_padding,
+            )?;
         }
 
         if abilities.len() < 3 {
@@ -63,13 +102,33 @@ impl AbilitiesView {
             }
         }
 
+        let stats = &character.stats;
+        let bar = Rect::new(
+            settings.position.x + LEADING_PADDING,
+            settings.position.y + background.height() as f32
+                - (LEADING_PADDING + settings.energy_bar_height) / 2.0,
+            background.width() as f32 - LEADING_PADDING * 2.0,
+            settings.energy_bar_height,
+        );
+        let energy = stats.energy as f32 / stats.max_energy.max(1) as f32;
+        graphics::set_color(ctx, settings.energy_bar_background_color)?;
+        graphics::rectangle(ctx, graphics::DrawMode::Fill, bar)?;
+        if energy > 0.0 {
+            graphics::set_color(ctx, settings.energy_bar_color)?;
+            graphics::rectangle(
+                ctx,
+                graphics::DrawMode::Fill,
+                Rect::new(bar.x, bar.y, bar.w * energy, bar.h),
+            )?;
+        }
+
         Ok(())
     }
 
     fn draw_ability_at_index(
         &self,
         ctx: &mut Context,
-        ability: Option<&Ability>,
+        ability: Option<(&Ability, bool)>,
         index: u32,
         horizontal_padding: f32,
     ) -> GameResult<()> {
@@ -84,15 +143,26 @@ impl AbilitiesView {
         };
 
         let background = &(settings.background.borrow().0);
-        if let Some(ability) = ability {
-            let icon = &(ability.icon.borrow().0);
+        if let Some((ability, affordable)) = ability {
+            let icon = if affordable {
+                ability.icon()
+            } else {
+                ability.inactive_icon()
+            };
+            let icon = &(icon.borrow().0);
             let pos = get_pos(
                 icon.width() as f32,
                 icon.height() as f32,
                 background.height() as f32,
             );
-            graphics::set_color(ctx, graphics::WHITE)?;
-            graphics::draw(ctx, &(ability.icon.borrow().0), pos, 0.0)?;
+            let tint = match ability.status {
+                _ if !affordable || ability.has_own_icon() => graphics::WHITE,
+                AbilityStatus::InStock => settings.in_stock_tint,
+                AbilityStatus::Active => settings.active_tint,
+                AbilityStatus::Inactive => graphics::WHITE,
+            };
+            graphics::set_color(ctx, tint)?;
+            graphics::draw(ctx, icon, pos, 0.0)?;
         } else {
             let pos = get_pos(70.0, 70.0, background.height() as f32);
             graphics::set_color(ctx, graphics::BLACK)?;
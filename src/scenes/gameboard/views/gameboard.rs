@@ -1,12 +1,27 @@
-use super::super::models::{Gameboard, Point};
+use super::super::models::{self, Cage, EdgeKind, Effects, Gameboard, Parity, Point, Rule};
 use common::colors;
 use common::resources;
 use common::util::*;
 use ggez::graphics::{self, Color, Point2, Text};
 use ggez::{Context, GameResult};
+use std::collections::{HashMap, HashSet};
+use sudoku::Element;
 use warmy;
 use world::World;
 
+/// Numbers cycle through these colors, so a 16x16 board repeats them.
+const NUMBER_COLORS: [Color; 9] = [
+    colors::RED,
+    colors::ORANGE,
+    colors::YELLOW,
+    colors::LIGHT_GREEN,
+    colors::GREEN,
+    colors::LIGHT_BLUE,
+    colors::BLUE,
+    colors::INDIGO,
+    colors::PURPLE,
+];
+
 #[derive(Debug, Clone)]
 struct NumberView(Text, Color);
 
@@ -29,9 +44,41 @@ pub struct GameboardViewSettings {
     pub section_edge_radius: f32,
     pub cell_edge_radius: f32,
     pub selected_cell_background_color: Color,
+    pub notes_mode_cell_background_color: Color,
+    pub conflict_cell_background_color: Color,
+    /// Whether cells that break a rule are highlighted at all.
+    pub show_conflicts: bool,
+    pub hint_cell_background_color: Color,
+    pub hint_target_cell_background_color: Color,
     pub text_color: Color,
-    numbers: [NumberView; 10],
+    pub note_text_color: Color,
+    pub cage_edge_color: Color,
+    pub cage_edge_radius: f32,
+    pub cage_dash_length: f32,
+    /// How far cage outlines sit inside their cells, as a fraction of a cell.
+    pub cage_inset: f32,
+    pub cage_sum_color: Color,
+    pub diagonal_color: Color,
+    pub diagonal_radius: f32,
+    /// Odd cells are shaded with a circle and even cells with a square.
+    pub parity_color: Color,
+    /// How much of a cell the parity shading covers.
+    pub parity_size: f32,
+    /// Cells an opponent's attack is about to land on.
+    pub warned_cell_background_color: Color,
+    pub locked_cell_color: Color,
+    /// Covers cells whose digits an opponent has hidden.
+    pub hidden_cell_color: Color,
+    pub mistake_flash_color: Color,
+    pub reveal_flash_color: Color,
+    pub check_flash_color: Color,
+    /// Covers the cells an ability is being aimed at.
+    pub target_highlight_color: Color,
+    /// How long, in seconds, a flashing cell takes to fade out.
+    pub flash_duration: f32,
+    numbers: Vec<NumberView>,
     background: warmy::Res<resources::Image>,
+    cage_sum_font: warmy::Res<resources::Font>,
 }
 
 impl GameboardViewSettings {
@@ -47,24 +94,25 @@ impl GameboardViewSettings {
                 ctx,
             ).unwrap();
         let font = &(warmy_font.borrow().0);
-        let numbers = [
-            NumberView(Text::new(ctx, "0", &font)?, colors::BLACK),
-            NumberView(Text::new(ctx, "1", &font)?, colors::RED),
-            NumberView(Text::new(ctx, "2", &font)?, colors::ORANGE),
-            NumberView(Text::new(ctx, "3", &font)?, colors::YELLOW),
-            NumberView(Text::new(ctx, "4", &font)?, colors::LIGHT_GREEN),
-            NumberView(Text::new(ctx, "5", &font)?, colors::GREEN),
-            NumberView(Text::new(ctx, "6", &font)?, colors::LIGHT_BLUE),
-            NumberView(Text::new(ctx, "7", &font)?, colors::BLUE),
-            NumberView(Text::new(ctx, "8", &font)?, colors::INDIGO),
-            NumberView(Text::new(ctx, "9", &font)?, colors::PURPLE),
-        ];
+        let max_value = models::MAX_ORDER * models::MAX_ORDER;
+        let mut numbers = Vec::with_capacity(max_value as usize);
+        for value in 1..=max_value {
+            let glyph = models::glyph(value).to_string();
+            let color = NUMBER_COLORS[(value as usize - 1) % NUMBER_COLORS.len()];
+            numbers.push(NumberView(Text::new(ctx, &glyph, &font)?, color));
+        }
         let background = world
             .assets
             .get::<_, resources::Image>(
                 &warmy::FSKey::new(format!("/images/backgrounds/{}", background_image_asset)),
                 ctx,
             ).unwrap();
+        let cage_sum_font = world
+            .assets
+            .get::<_, resources::Font>(
+                &resources::FSFontKey::new("/fonts/Multicolore.ttf", 10),
+                ctx,
+            ).unwrap();
         Ok(GameboardViewSettings {
             position: Point2::new(55.0, 100.0),
             size: 400.0,
@@ -73,9 +121,33 @@ impl GameboardViewSettings {
             section_edge_radius: 4.0,
             cell_edge_radius: 1.0,
             selected_cell_background_color: From::from([0.9, 0.9, 1.0, 0.5]),
+            notes_mode_cell_background_color: From::from([1.0, 0.95, 0.8, 0.5]),
+            conflict_cell_background_color: From::from([1.0, 0.6, 0.6, 1.0]),
+            show_conflicts: true,
+            hint_cell_background_color: From::from([1.0, 0.95, 0.6, 1.0]),
+            hint_target_cell_background_color: From::from([0.6, 0.9, 0.6, 1.0]),
             text_color: colors::BLACK,
+            note_text_color: colors::DARK_GRAY,
+            cage_edge_color: colors::DARK_GRAY,
+            cage_edge_radius: 1.0,
+            cage_dash_length: 4.0,
+            cage_inset: 0.08,
+            cage_sum_color: colors::BLACK,
+            diagonal_color: From::from([0.2, 0.4, 0.9, 0.35]),
+            diagonal_radius: 3.0,
+            parity_color: From::from([0.85, 0.85, 0.85, 1.0]),
+            parity_size: 0.8,
+            warned_cell_background_color: From::from([1.0, 0.7, 0.3, 1.0]),
+            locked_cell_color: From::from([0.2, 0.2, 0.3, 0.45]),
+            hidden_cell_color: From::from([0.3, 0.3, 0.35, 1.0]),
+            mistake_flash_color: From::from([1.0, 0.1, 0.1, 0.8]),
+            reveal_flash_color: From::from([0.3, 0.9, 0.4, 0.8]),
+            check_flash_color: From::from([1.0, 0.5, 0.0, 0.7]),
+            target_highlight_color: From::from([0.3, 0.5, 1.0, 0.35]),
+            flash_duration: 0.6,
             numbers,
             background,
+            cage_sum_font,
         })
     }
 }
@@ -83,14 +155,47 @@ impl GameboardViewSettings {
 #[derive(Debug, Clone)]
 pub struct GameboardView {
     pub settings: GameboardViewSettings,
+    /// Rendered cage sums, made as they are first needed.
+    cage_sums: HashMap<u32, Text>,
+    /// Cells flashing a color, with how many seconds they have left out of
+    /// how many they started with.
+    flashes: Vec<(Point, Color, f32, f32)>,
 }
 
 impl GameboardView {
     pub fn new(settings: GameboardViewSettings) -> Self {
-        GameboardView { settings }
+        GameboardView {
+            settings,
+            cage_sums: HashMap::new(),
+            flashes: vec![],
+        }
     }
 
-    pub fn draw(&self, ctx: &mut Context, gameboard: &Gameboard) -> GameResult<()> {
+    /// Briefly covers a cell in `color`, fading out over `flash_duration`.
+    pub fn flash(&mut self, point: Point, color: Color) {
+        let duration = self.settings.flash_duration;
+        self.flash_for(point, color, duration);
+    }
+
+    /// Covers a cell in `color`, fading out over `duration` seconds.
+    pub fn flash_for(&mut self, point: Point, color: Color, duration: f32) {
+        self.flashes.retain(|&(other, _, _, _)| other != point);
+        self.flashes.push((point, color, duration, duration));
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        for flash in &mut self.flashes {
+            flash.2 -= delta;
+        }
+        self.flashes.retain(|&(_, _, left, _)| left > 0.0);
+    }
+
+    pub fn draw(
+        &mut self,
+        ctx: &mut Context,
+        gameboard: &Gameboard,
+        effects: &Effects,
+    ) -> GameResult<()> {
         use ggez::graphics::{DrawMode, Rect};
 
         let settings = &self.settings;
@@ -103,12 +208,65 @@ impl GameboardView {
             0.0,
         )?;
 
-        self.draw_numbers_for_board(ctx, gameboard, colors::GRAY)?;
+        self.draw_numbers_for_board(ctx, gameboard, effects, colors::GRAY)?;
+
+        let cell_size = self.cell_size(gameboard);
+        graphics::set_color(ctx, settings.locked_cell_color)?;
+        for &Point(x, y) in &effects.locked {
+            graphics::rectangle(
+                ctx,
+                DrawMode::Fill,
+                Rect::new(
+                    settings.position.x + f32::from(x) * cell_size,
+                    settings.position.y + f32::from(y) * cell_size,
+                    cell_size,
+                    cell_size,
+                ),
+            )?;
+        }
+        for &(Point(x, y), color, left, duration) in &self.flashes {
+            let faded = Color {
+                a: color.a * left / duration,
+                ..color
+            };
+            graphics::set_color(ctx, faded)?;
+            graphics::rectangle(
+                ctx,
+                DrawMode::Fill,
+                Rect::new(
+                    settings.position.x + f32::from(x) * cell_size,
+                    settings.position.y + f32::from(y) * cell_size,
+                    cell_size,
+                    cell_size,
+                ),
+            )?;
+        }
+
+        if gameboard.rules.rules().contains(&Rule::Diagonals) {
+            let (x, y, size) = (settings.position.x, settings.position.y, settings.size);
+            let mut mb = graphics::MeshBuilder::new();
+            mb.line(
+                &[Point2::new(x, y), Point2::new(x + size, y + size)],
+                settings.diagonal_radius,
+            );
+            mb.line(
+                &[Point2::new(x + size, y), Point2::new(x, y + size)],
+                settings.diagonal_radius,
+            );
+            let mesh = mb.build(ctx)?;
+            graphics::set_color(ctx, settings.diagonal_color)?;
+            graphics::draw_ex(ctx, &mesh, Default::default())?;
+        }
 
         if let Some(Point(x, y)) = gameboard.selected_cell {
-            let cell_size = settings.size / 9.0;
+            let cell_size = self.cell_size(gameboard);
             let pos = Point2::new(x as f32 * cell_size, y as f32 * cell_size);
-            graphics::set_color(ctx, settings.selected_cell_background_color)?;
+            let background_color = if gameboard.notes_mode {
+                settings.notes_mode_cell_background_color
+            } else {
+                settings.selected_cell_background_color
+            };
+            graphics::set_color(ctx, background_color)?;
             graphics::rectangle(
                 ctx,
                 DrawMode::Fill,
@@ -121,14 +279,75 @@ impl GameboardView {
             )?;
         }
 
-        let cell_edge_mesh = self.build_cell_edge_mesh(ctx, 9, 3)?;
+        let cell_edge_mesh =
+            self.build_edge_mesh(ctx, gameboard, EdgeKind::Cell, settings.cell_edge_radius)?;
         graphics::set_color(ctx, settings.cell_edge_color)?;
         graphics::draw_ex(ctx, &cell_edge_mesh, Default::default())?;
 
-        let section_edge_mesh = self.build_section_edge_mesh(ctx, 3)?;
+        let section_edge_mesh = self.build_edge_mesh(
+            ctx,
+            gameboard,
+            EdgeKind::Section,
+            settings.section_edge_radius,
+        )?;
         graphics::set_color(ctx, settings.section_edge_color)?;
         graphics::draw_ex(ctx, &section_edge_mesh, Default::default())?;
 
+        for cage in &gameboard.cages {
+            self.draw_cage(ctx, gameboard, cage)?;
+        }
+
+        Ok(())
+    }
+
+    /// Cages are drawn as a dashed outline just inside their cells, with the
+    /// sum in the top left corner.
+    fn draw_cage(&mut self, ctx: &mut Context, board: &Gameboard, cage: &Cage) -> GameResult<()> {
+        let cell_size = self.cell_size(board);
+        let settings = &self.settings;
+        let at = |(x, y): (f32, f32)| {
+            Point2::new(
+                settings.position.x + x * cell_size,
+                settings.position.y + y * cell_size,
+            )
+        };
+
+        let mut mb = graphics::MeshBuilder::new();
+        for (from, to) in cage.outline(settings.cage_inset) {
+            let (from, to) = (at(from), at(to));
+            let length = (to - from).norm();
+            let dashes = (length / (settings.cage_dash_length * 2.0)).ceil().max(1.0) as usize;
+            for i in 0..dashes {
+                let start = i as f32 * settings.cage_dash_length * 2.0;
+                let end = (start + settings.cage_dash_length).min(length);
+                let dash = &[
+                    from + (to - from) * (start / length),
+                    from + (to - from) * (end / length),
+                ];
+                mb.line(dash, settings.cage_edge_radius);
+            }
+        }
+        let mesh = mb.build(ctx)?;
+        graphics::set_color(ctx, settings.cage_edge_color)?;
+        graphics::draw_ex(ctx, &mesh, Default::default())?;
+
+        let Point(x, y) = cage.anchor();
+        let corner = at((
+            f32::from(x) + settings.cage_inset,
+            f32::from(y) + settings.cage_inset,
+        ));
+        if !self.cage_sums.contains_key(&cage.sum) {
+            let font = &(self.settings.cage_sum_font.borrow().0);
+            let text = Text::new(ctx, &cage.sum.to_string(), font)?;
+            self.cage_sums.insert(cage.sum, text);
+        }
+        graphics::set_color(ctx, self.settings.cage_sum_color)?;
+        graphics::draw(
+            ctx,
+            &self.cage_sums[&cage.sum],
+            Point2::new(corner.x + 1.0, corner.y + 1.0),
+            0.0,
+        )?;
         Ok(())
     }
 
@@ -136,98 +355,219 @@ impl GameboardView {
         &self,
         ctx: &mut Context,
         board: &Gameboard,
+        effects: &Effects,
         background: Color,
     ) -> GameResult<()> {
         use ggez::graphics::{DrawMode, Rect};
-        use sudoku::Element;
 
         let settings = &self.settings;
-        let size = settings.size / 9.0;
+        let size = self.cell_size(board);
+        let conflicts = if settings.show_conflicts {
+            board.conflicts()
+        } else {
+            HashSet::new()
+        };
+        let (hint_cells, hint_target) = match board.hint {
+            Some(Ok(ref hint)) => (hint.cells(), hint.placement().map(|(point, _)| point)),
+            _ => (vec![], None),
+        };
 
         for point in board.points() {
-            let x = point[0];
-            let y = point[1];
+            let Point(x, y) = point;
             let cell = Rect::new(
                 settings.position.x + x as f32 * size,
                 settings.position.y + y as f32 * size,
                 size,
                 size,
             );
-            if let Some(Element(value)) = board.current[point] {
-                if !board.is_mutable(Point(x, y)) {
-                    graphics::set_color(ctx, background)?;
-                    graphics::rectangle(ctx, DrawMode::Fill, cell)?;
-                }
-                let text = &settings.numbers[value as usize];
+            let value = board.current[point];
+            if effects.hidden.contains(&point) {
+                graphics::set_color(ctx, settings.hidden_cell_color)?;
+                graphics::rectangle(ctx, DrawMode::Fill, cell)?;
+                continue;
+            }
+            let cell_background = if effects.warned.contains(&point) {
+                Some(settings.warned_cell_background_color)
+            } else if conflicts.contains(&point) {
+                Some(settings.conflict_cell_background_color)
+            } else if hint_target == Some(point) {
+                Some(settings.hint_target_cell_background_color)
+            } else if hint_cells.contains(&point) {
+                Some(settings.hint_cell_background_color)
+            } else if value.is_some() && !board.is_mutable(point) {
+                Some(background)
+            } else {
+                None
+            };
+            if let Some(color) = cell_background {
+                graphics::set_color(ctx, color)?;
+                graphics::rectangle(ctx, DrawMode::Fill, cell)?;
+            }
+            if let Some(parity) = board.rules.parity(point) {
+                self.draw_parity(ctx, parity, cell)?;
+            }
+            if let Some(value) = value {
+                let text = self.number(value);
+                // Numbers take up about half the height of a cell
+                let text_scale = size / (2.0 * text.height() as f32);
                 let text_pos = center_rect_in_rect(
                     Rect::new(
                         0.0,
                         0.0,
-                        text.width() as f32 / 2.0,
-                        text.height() as f32 / 2.0,
+                        text.width() as f32 * text_scale,
+                        text.height() as f32 * text_scale,
                     ),
                     cell,
                 );
-                let text_scale = Point2::new(0.5, 0.5);
                 graphics::draw_ex(
                     ctx,
                     &text.0,
                     graphics::DrawParam {
-                        dest: Point2::new(text_pos.x, text_pos.y + 4.0),
+                        dest: Point2::new(text_pos.x, text_pos.y + 8.0 * text_scale),
                         color: Some(text.1),
-                        scale: text_scale,
+                        scale: Point2::new(text_scale, text_scale),
                         ..Default::default()
                     },
                 )?;
+            } else {
+                self.draw_notes_for_cell(ctx, board, point, cell)?;
             }
         }
         Ok(())
     }
 
-    fn build_cell_edge_mesh(
+    fn draw_parity(
         &self,
         ctx: &mut Context,
-        cells: i32,
-        cells_per_section: i32,
-    ) -> GameResult<graphics::Mesh> {
+        parity: Parity,
+        cell: graphics::Rect,
+    ) -> GameResult<()> {
+        use ggez::graphics::{DrawMode, Rect};
+
         let settings = &self.settings;
-        let mut mb = graphics::MeshBuilder::new();
-        for i in 0..cells {
-            if (i % cells_per_section) == 0 {
-                continue;
-            }
-            let x = settings.position.x + i as f32 / 9.0 * settings.size;
-            let y = settings.position.y + i as f32 / 9.0 * settings.size;
-            let x2 = settings.position.x + settings.size;
-            let y2 = settings.position.y + settings.size;
+        let size = cell.w * settings.parity_size;
+        let corner = center_rect_in_rect(Rect::new(0.0, 0.0, size, size), cell);
+        graphics::set_color(ctx, settings.parity_color)?;
+        match parity {
+            Parity::Odd => graphics::circle(
+                ctx,
+                DrawMode::Fill,
+                Point2::new(corner.x + size / 2.0, corner.y + size / 2.0),
+                size / 2.0,
+                0.5,
+            ),
+            Parity::Even => graphics::rectangle(
+                ctx,
+                DrawMode::Fill,
+                Rect::new(corner.x, corner.y, size, size),
+            ),
+        }
+    }
+
+    /// Notes are laid out as a small grid inside the cell, one slot per
+    /// value, with each value always drawn in the same slot.
+    fn draw_notes_for_cell(
+        &self,
+        ctx: &mut Context,
+        board: &Gameboard,
+        point: Point,
+        cell: graphics::Rect,
+    ) -> GameResult<()> {
+        use ggez::graphics::Rect;
+
+        let settings = &self.settings;
+        let slots = board.order() as u32;
+        let slot_size = cell.w / slots as f32;
+
+        for value in board.notes.values(point) {
+            let Element(index) = value;
+            let index = index as u32 - 1;
+            let slot = Rect::new(
+                cell.x + (index % slots) as f32 * slot_size,
+                cell.y + (index / slots) as f32 * slot_size,
+                slot_size,
+                slot_size,
+            );
+            let text = self.number(value);
+            let text_scale = slot_size * 0.75 / text.height() as f32;
+            let text_pos = center_rect_in_rect(
+                Rect::new(
+                    0.0,
+                    0.0,
+                    text.width() as f32 * text_scale,
+                    text.height() as f32 * text_scale,
+                ),
+                slot,
+            );
+            graphics::draw_ex(
+                ctx,
+                &text.0,
+                graphics::DrawParam {
+                    dest: Point2::new(text_pos.x, text_pos.y + 4.0 * text_scale),
+                    color: Some(settings.note_text_color),
+                    scale: Point2::new(text_scale, text_scale),
+                    ..Default::default()
+                },
+            )?;
+        }
+        Ok(())
+    }
 
-            let vline = &[Point2::new(x, settings.position.y), Point2::new(x, y2)];
-            mb.line(vline, settings.cell_edge_radius);
+    /// Covers `points` for showing what an ability is being aimed at.
+    pub fn draw_highlight(
+        &self,
+        ctx: &mut Context,
+        gameboard: &Gameboard,
+        points: &[Point],
+    ) -> GameResult<()> {
+        use ggez::graphics::{DrawMode, Rect};
 
-            let hline = &[Point2::new(settings.position.x, y), Point2::new(x2, y)];
-            mb.line(hline, settings.cell_edge_radius);
+        let settings = &self.settings;
+        let cell_size = self.cell_size(gameboard);
+        graphics::set_color(ctx, settings.target_highlight_color)?;
+        for &Point(x, y) in points {
+            graphics::rectangle(
+                ctx,
+                DrawMode::Fill,
+                Rect::new(
+                    settings.position.x + f32::from(x) * cell_size,
+                    settings.position.y + f32::from(y) * cell_size,
+                    cell_size,
+                    cell_size,
+                ),
+            )?;
         }
-        mb.build(ctx)
+        Ok(())
     }
 
-    fn build_section_edge_mesh(
+    fn cell_size(&self, board: &Gameboard) -> f32 {
+        let dimension = board.order() * board.order();
+        self.settings.size / f32::from(dimension)
+    }
+
+    fn number(&self, value: Element) -> &NumberView {
+        let Element(value) = value;
+        &self.settings.numbers[value as usize - 1]
+    }
+
+    fn build_edge_mesh(
         &self,
         ctx: &mut Context,
-        sections: i32,
+        board: &Gameboard,
+        kind: EdgeKind,
+        radius: f32,
     ) -> GameResult<graphics::Mesh> {
         let settings = &self.settings;
+        let cell_size = self.cell_size(board);
+        let at = |(x, y): (u8, u8)| {
+            Point2::new(
+                settings.position.x + f32::from(x) * cell_size,
+                settings.position.y + f32::from(y) * cell_size,
+            )
+        };
         let mut mb = graphics::MeshBuilder::new();
-        for i in 1..sections {
-            let x = settings.position.x + i as f32 / 3.0 * settings.size;
-            let y = settings.position.y + i as f32 / 3.0 * settings.size;
-            let x2 = settings.position.x + settings.size;
-            let y2 = settings.position.y + settings.size;
-
-            let vline = &[Point2::new(x, settings.position.y), Point2::new(x, y2)];
-            mb.line(vline, settings.section_edge_radius);
-
-            let hline = &[Point2::new(settings.position.x, y), Point2::new(x2, y)];
-            mb.line(hline, settings.section_edge_radius);
+        for edge in board.edges().into_iter().filter(|edge| edge.kind == kind) {
+            mb.line(&[at(edge.from), at(edge.to)], radius);
         }
         mb.build(ctx)
     }
@@ -0,0 +1,108 @@
+use super::super::models::Gameboard;
+use common::colors;
+use common::resources;
+use ggez::graphics::{self, Color, DrawParam, Point2, Text};
+use ggez::{Context, GameResult};
+use warmy;
+use world::World;
+
+const PADDING: f32 = 12.0;
+
+#[derive(Debug, Clone)]
+pub struct HintViewSettings {
+    pub position: Point2,
+    pub text_color: Color,
+    background: warmy::Res<resources::Image>,
+    font: warmy::Res<resources::Font>,
+}
+
+impl HintViewSettings {
+    pub fn new(ctx: &mut Context, world: &mut World) -> Self {
+        let background = world
+            .assets
+            .get::<_, resources::Image>(&warmy::FSKey::new("/images/ui/other-container.png"), ctx)
+            .unwrap();
+        let font = world
+            .assets
+            .get::<_, resources::Font>(
+                &resources::FSFontKey::new("/fonts/Multicolore.ttf", 11),
+                ctx,
+            ).unwrap();
+        HintViewSettings {
+            position: Point2::new(500.0, 325.0),
+            text_color: colors::BLACK,
+            background,
+            font,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HintView {
+    pub settings: HintViewSettings,
+    message: Option<String>,
+    lines: Vec<Text>,
+}
+
+impl HintView {
+    pub fn new(settings: HintViewSettings) -> Self {
+        HintView {
+            settings,
+            message: None,
+            lines: vec![],
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, gameboard: &Gameboard) -> GameResult<()> {
+        let message = match gameboard.hint {
+            Some(Ok(ref hint)) => hint.explanation(),
+            Some(Err(ref err)) => err.to_string(),
+            None => return Ok(()),
+        };
+        if self.message.as_ref() != Some(&message) {
+            self.layout(ctx, message)?;
+        }
+
+        let settings = &self.settings;
+        let background = &(settings.background.borrow().0);
+        let line_height = self.lines.first().map_or(0, |line| line.height()) as f32;
+        let content_height = line_height * self.lines.len() as f32 + PADDING * 2.0;
+        let scale_y = (content_height / background.height() as f32).max(1.0);
+
+        graphics::set_color(ctx, graphics::WHITE)?;
+        graphics::draw_ex(
+            ctx,
+            background,
+            DrawParam {
+                dest: settings.position,
+                scale: Point2::new(1.0, scale_y),
+                ..Default::default()
+            },
+        )?;
+
+        graphics::set_color(ctx, settings.text_color)?;
+        for (i, line) in self.lines.iter().enumerate() {
+            let pos = Point2::new(
+                settings.position.x + PADDING,
+                settings.position.y + PADDING + i as f32 * line_height,
+            );
+            graphics::draw(ctx, line, pos, 0.0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Text has to be rendered with a `Context`, so the wrapped lines are
+    /// cached until the message changes.
+    fn layout(&mut self, ctx: &mut Context, message: String) -> GameResult<()> {
+        let font = &(self.settings.font.borrow().0);
+        let width = self.settings.background.borrow().0.width() as f32 - PADDING * 2.0;
+        let (_, wrapped) = font.get_wrap(&message, width as usize);
+        self.lines = wrapped
+            .iter()
+            .map(|line| Text::new(ctx, line, font))
+            .collect::<GameResult<_>>()?;
+        self.message = Some(message);
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use common::colors;
+use common::resources;
+use ggez::graphics::{self, Color, Point2, Text};
+use ggez::{Context, GameResult};
+use warmy;
+use world::World;
+
+#[derive(Debug, Clone)]
+pub struct LabelViewSettings {
+    pub position: Point2,
+    pub text_color: Color,
+    font: warmy::Res<resources::Font>,
+}
+
+impl LabelViewSettings {
+    pub fn new(position: Point2, points: u32, ctx: &mut Context, world: &mut World) -> Self {
+        let font = world
+            .assets
+            .get::<_, resources::Font>(
+                &resources::FSFontKey::new("/fonts/Multicolore.ttf", points),
+                ctx,
+            ).unwrap();
+        LabelViewSettings {
+            position,
+            text_color: colors::BLACK,
+            font,
+        }
+    }
+}
+
+/// A single line of text. Rendering text needs a `Context`, so the rendered
+/// line is cached and only rebuilt when the string changes.
+#[derive(Debug, Clone)]
+pub struct LabelView {
+    pub settings: LabelViewSettings,
+    text: Option<(String, Text)>,
+}
+
+impl LabelView {
+    pub fn new(settings: LabelViewSettings) -> Self {
+        LabelView {
+            settings,
+            text: None,
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, string: &str) -> GameResult<()> {
+        let is_stale = match self.text {
+            Some((ref cached, _)) => cached != string,
+            None => true,
+        };
+        if is_stale {
+            let font = &(self.settings.font.borrow().0);
+            let text = Text::new(ctx, string, font)?;
+            self.text = Some((string.to_string(), text));
+        }
+
+        if let Some((_, ref text)) = self.text {
+            graphics::set_color(ctx, self.settings.text_color)?;
+            graphics::draw(ctx, text, self.settings.position, 0.0)?;
+        }
+        Ok(())
+    }
+}
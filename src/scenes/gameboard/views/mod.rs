@@ -1,11 +1,15 @@
 mod abilities;
 mod background;
 mod gameboard;
+mod hint;
+mod label;
 mod portrait;
 mod timer;
 
 pub use self::abilities::*;
 pub use self::background::*;
 pub use self::gameboard::*;
+pub use self::hint::*;
+pub use self::label::*;
 pub use self::portrait::*;
 pub use self::timer::*;
@@ -1,7 +1,8 @@
 use super::super::models::{Character, CharacterKind};
+use common::colors;
 use common::resources;
 use common::util::*;
-use ggez::graphics::{self, Point2, Rect};
+use ggez::graphics::{self, Color, DrawMode, Point2, Rect};
 use ggez::{Context, GameResult};
 use warmy;
 use world::World;
@@ -9,6 +10,14 @@ use world::World;
 #[derive(Debug, Clone)]
 pub struct PortraitViewSettings {
     pub position: Point2,
+    pub hp_bar_height: f32,
+    /// Space between the portrait and the HP bar under it.
+    pub hp_bar_gap: f32,
+    pub hp_bar_color: Color,
+    /// Used instead once HP drops to `low_hp` of the maximum.
+    pub hp_bar_low_color: Color,
+    pub hp_bar_background_color: Color,
+    pub low_hp: f32,
     background: warmy::Res<resources::Image>,
 }
 
@@ -31,6 +40,12 @@ impl PortraitViewSettings {
             ).unwrap();
         PortraitViewSettings {
             position,
+            hp_bar_height: 6.0,
+            hp_bar_gap: 1.0,
+            hp_bar_color: colors::GREEN,
+            hp_bar_low_color: colors::RED,
+            hp_bar_background_color: colors::DARK_GRAY,
+            low_hp: 0.3,
             background,
         }
     }
@@ -72,6 +87,31 @@ impl PortraitView {
         );
         graphics::draw(ctx, character_image, pos, 0.0)?;
 
+        let width = background_image.width() as f32;
+        let bar = Rect::new(
+            settings.position.x,
+            settings.position.y + background_image.height() as f32 + settings.hp_bar_gap,
+            width,
+            settings.hp_bar_height,
+        );
+        let stats = &character.stats;
+        let hp = stats.hp as f32 / stats.max_hp.max(1) as f32;
+        graphics::set_color(ctx, settings.hp_bar_background_color)?;
+        graphics::rectangle(ctx, DrawMode::Fill, bar)?;
+        if hp > 0.0 {
+            let color = if hp <= settings.low_hp {
+                settings.hp_bar_low_color
+            } else {
+                settings.hp_bar_color
+            };
+            graphics::set_color(ctx, color)?;
+            graphics::rectangle(
+                ctx,
+                DrawMode::Fill,
+                Rect::new(bar.x, bar.y, bar.w * hp, bar.h),
+            )?;
+        }
+
         Ok(())
     }
 }
@@ -1,5 +1,7 @@
+use common::colors;
 use common::resources;
-use ggez::graphics::{self, Point2};
+use common::util::*;
+use ggez::graphics::{self, Color, Point2, Rect, Text};
 use ggez::{Context, GameResult};
 use warmy;
 use world::World;
@@ -7,7 +9,11 @@ use world::World;
 #[derive(Debug, Clone)]
 pub struct TimerViewSettings {
     pub position: Point2,
+    pub text_color: Color,
+    /// The text color while the clock is stopped.
+    pub frozen_text_color: Color,
     background: warmy::Res<resources::Image>,
+    font: warmy::Res<resources::Font>,
 }
 
 impl TimerViewSettings {
@@ -16,33 +22,74 @@ impl TimerViewSettings {
             .assets
             .get::<_, resources::Image>(&warmy::FSKey::new("/images/ui/timer-container.png"), ctx)
             .unwrap();
+        let font = world
+            .assets
+            .get::<_, resources::Font>(
+                &resources::FSFontKey::new("/fonts/Multicolore.ttf", 36),
+                ctx,
+            ).unwrap();
         TimerViewSettings {
             position: Point2::new(500.0, 435.0),
+            text_color: colors::BLACK,
+            frozen_text_color: colors::BLUE,
             background,
+            font,
         }
     }
 }
 
+/// The game clock, shown as minutes and seconds. The rendered text is cached
+/// and only rebuilt when the shown time changes.
 #[derive(Debug, Clone)]
 pub struct TimerView {
     pub settings: TimerViewSettings,
+    text: Option<(u64, Text)>,
 }
 
 impl TimerView {
     pub fn new(settings: TimerViewSettings) -> Self {
-        TimerView { settings }
+        TimerView {
+            settings,
+            text: None,
+        }
     }
 
-    pub fn draw(&self, ctx: &mut Context, _time: u64) -> GameResult<()> {
-        let settings = &self.settings;
+    /// Draws `seconds` as mm:ss, in another color if the clock is `frozen`.
+    pub fn draw(&mut self, ctx: &mut Context, seconds: u64, frozen: bool) -> GameResult<()> {
+        let is_stale = self
+            .text
+            .as_ref()
+            .map_or(true, |&(shown, _)| shown != seconds);
+        if is_stale {
+            let font = &(self.settings.font.borrow().0);
+            let clock = format_clock(seconds);
+            self.text = Some((seconds, Text::new(ctx, &clock, font)?));
+        }
 
+        let settings = &self.settings;
+        let background = &(settings.background.borrow().0);
         graphics::set_color(ctx, graphics::WHITE)?;
-        graphics::draw(
-            ctx,
-            &(settings.background.borrow().0),
-            settings.position,
-            0.0,
-        )?;
+        graphics::draw(ctx, background, settings.position, 0.0)?;
+
+        if let Some((_, ref text)) = self.text {
+            let container = Rect::new(
+                settings.position.x,
+                settings.position.y,
+                background.width() as f32,
+                background.height() as f32,
+            );
+            let text_pos = center_rect_in_rect(
+                Rect::new(0.0, 0.0, text.width() as f32, text.height() as f32),
+                container,
+            );
+            let color = if frozen {
+                settings.frozen_text_color
+            } else {
+                settings.text_color
+            };
+            graphics::set_color(ctx, color)?;
+            graphics::draw(ctx, text, text_pos, 0.0)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,234 @@
+//! A list of choices down the middle of the screen, shared by the scenes
+//! that are nothing more than a menu. Items are picked with the vertical
+//! axis and `Select`, or by clicking on them. Lines of text can go between
+//! the title and the items, for anything the player just needs to read.
+
+use common::colors;
+use common::resources;
+use ggez::graphics::{self, Color, DrawMode, Point2, Rect, Text};
+use ggez::{Context, GameResult};
+use input::{self, events::InputEffect, Axis, Button};
+use warmy;
+use world::World;
+
+const BACKGROUND_ASSET: &str = "/images/backgrounds/area-1.png";
+const PANEL: (f32, f32, f32) = (200.0, 60.0, 400.0);
+const TITLE_TOP: f32 = 90.0;
+const LINES_TOP: f32 = 150.0;
+const LINE_HEIGHT: f32 = 26.0;
+const ITEMS_TOP: f32 = 180.0;
+const ITEM_HEIGHT: f32 = 50.0;
+const PANEL_PADDING: f32 = 30.0;
+
+struct MenuAssets {
+    background: warmy::Res<resources::Image>,
+    title_font: warmy::Res<resources::Font>,
+    line_font: warmy::Res<resources::Font>,
+    item_font: warmy::Res<resources::Font>,
+}
+
+pub struct Menu<T> {
+    title: String,
+    lines: Vec<String>,
+    items: Vec<(String, T)>,
+    selected: usize,
+    pub panel_color: Color,
+    pub text_color: Color,
+    pub selected_color: Color,
+    /// Loaded on the first draw, since scenes are made without a `Context`.
+    assets: Option<MenuAssets>,
+    /// Rendered title, lines and item labels, rebuilt whenever one changes.
+    texts: Vec<(String, Text)>,
+}
+
+impl<T: Copy + PartialEq> Menu<T> {
+    pub fn new(title: &str, items: Vec<(&str, T)>) -> Self {
+        Menu {
+            title: title.to_string(),
+            lines: vec![],
+            items: items
+                .into_iter()
+                .map(|(label, choice)| (label.to_string(), choice))
+                .collect(),
+            selected: 0,
+            panel_color: From::from([1.0, 1.0, 1.0, 0.85]),
+            text_color: colors::BLACK,
+            selected_color: From::from([0.9, 0.9, 1.0, 1.0]),
+            assets: None,
+            texts: vec![],
+        }
+    }
+
+    pub fn with_lines(mut self, lines: Vec<String>) -> Self {
+        self.lines = lines;
+        self
+    }
+
+    pub fn set_label(&mut self, choice: T, label: &str) {
+        for item in &mut self.items {
+            if item.1 == choice {
+                item.0 = label.to_string();
+            }
+        }
+    }
+
+    /// Moves the highlight to `choice`, if it's in the menu.
+    pub fn select(&mut self, choice: T) {
+        if let Some(index) = self.items.iter().position(|item| item.1 == choice) {
+            self.selected = index;
+        }
+    }
+
+    /// Returns the choice the player made, if this input made one.
+    pub fn input(&mut self, ev: input::InputEvent, started: bool) -> Option<T> {
+        match ev {
+            InputEffect::Axis(Axis::Vert, is_up) if !started => {
+                let count = self.items.len();
+                self.selected = if is_up {
+                    (self.selected + count - 1) % count
+                } else {
+                    (self.selected + 1) % count
+                };
+                None
+            }
+            InputEffect::Button(Button::Select, None) if started => {
+                Some(self.items[self.selected].1)
+            }
+            InputEffect::Button(Button::Select, Some((x, y))) if started => {
+                let index = self.item_at(x, y)?;
+                self.selected = index;
+                Some(self.items[index].1)
+            }
+            InputEffect::MouseMotion(x, y, _, _) => {
+                if let Some(index) = self.item_at(x, y) {
+                    self.selected = index;
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Items start lower when there are lines to make room for.
+    fn items_top(&self) -> f32 {
+        if self.lines.is_empty() {
+            ITEMS_TOP
+        } else {
+            LINES_TOP + (self.lines.len() as f32 + 0.5) * LINE_HEIGHT
+        }
+    }
+
+    fn item_at(&self, x: i32, y: i32) -> Option<usize> {
+        let (left, _, width) = PANEL;
+        let (x, y) = (x as f32, y as f32);
+        let items_top = self.items_top();
+        if x < left || x >= left + width || y < items_top {
+            return None;
+        }
+        let index = ((y - items_top) / ITEM_HEIGHT) as usize;
+        if index < self.items.len() {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, world: &mut World) -> GameResult<()> {
+        if self.assets.is_none() {
+            self.assets = Some(Self::load(ctx, world));
+        }
+        let labels: Vec<&String> = Some(&self.title)
+            .into_iter()
+            .chain(&self.lines)
+            .chain(self.items.iter().map(|item| &item.0))
+            .collect();
+        let is_stale = labels.len() != self.texts.len()
+            || labels
+                .iter()
+                .zip(&self.texts)
+                .any(|(label, cached)| **label != cached.0);
+        if is_stale {
+            let assets = self.assets.as_ref().unwrap();
+            let line_count = self.lines.len();
+            let mut texts = Vec::with_capacity(labels.len());
+            for (i, label) in labels.into_iter().enumerate() {
+                let font = if i == 0 {
+                    &assets.title_font
+                } else if i <= line_count {
+                    &assets.line_font
+                } else {
+                    &assets.item_font
+                };
+                let text = Text::new(ctx, label, &(font.borrow().0))?;
+                texts.push((label.clone(), text));
+            }
+            self.texts = texts;
+        }
+
+        let assets = self.assets.as_ref().unwrap();
+        graphics::set_color(ctx, graphics::WHITE)?;
+        graphics::draw(
+            ctx,
+            &(assets.background.borrow().0),
+            Point2::new(0.0, 0.0),
+            0.0,
+        )?;
+
+        let (left, top, width) = PANEL;
+        let items_top = self.items_top();
+        let bottom = items_top + self.items.len() as f32 * ITEM_HEIGHT + PANEL_PADDING;
+        graphics::set_color(ctx, self.panel_color)?;
+        graphics::rectangle(
+            ctx,
+            DrawMode::Fill,
+            Rect::new(left, top, width, bottom - top),
+        )?;
+        graphics::set_color(ctx, self.selected_color)?;
+        graphics::rectangle(
+            ctx,
+            DrawMode::Fill,
+            Rect::new(
+                left,
+                items_top + self.selected as f32 * ITEM_HEIGHT,
+                width,
+                ITEM_HEIGHT,
+            ),
+        )?;
+
+        graphics::set_color(ctx, self.text_color)?;
+        let line_count = self.lines.len();
+        for (i, &(_, ref text)) in self.texts.iter().enumerate() {
+            let y = if i == 0 {
+                TITLE_TOP
+            } else if i <= line_count {
+                LINES_TOP + (i - 1) as f32 * LINE_HEIGHT
+            } else {
+                let row = items_top + (i - 1 - line_count) as f32 * ITEM_HEIGHT;
+                row + (ITEM_HEIGHT - text.height() as f32) / 2.0
+            };
+            let x = left + (width - text.width() as f32) / 2.0;
+            graphics::draw(ctx, text, Point2::new(x, y), 0.0)?;
+        }
+        Ok(())
+    }
+
+    fn load(ctx: &mut Context, world: &mut World) -> MenuAssets {
+        let font = |points, ctx: &mut Context, world: &mut World| {
+            world
+                .assets
+                .get::<_, resources::Font>(
+                    &resources::FSFontKey::new("/fonts/Multicolore.ttf", points),
+                    ctx,
+                ).unwrap()
+        };
+        MenuAssets {
+            background: world
+                .assets
+                .get::<_, resources::Image>(&warmy::FSKey::new(BACKGROUND_ASSET), ctx)
+                .unwrap(),
+            title_font: font(36, ctx, world),
+            line_font: font(18, ctx, world),
+            item_font: font(22, ctx, world),
+        }
+    }
+}
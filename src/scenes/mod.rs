@@ -1,10 +1,21 @@
+pub mod difficulty;
 pub mod gameboard;
+pub mod menu;
+pub mod packs;
+pub mod pause;
+pub mod result;
+pub mod settings;
+pub mod statistics;
+pub mod title;
 
 use ggez_goodies::scene;
 use input;
 use world::World;
 
 pub use self::gameboard::*;
+pub use self::pause::{PauseChoice, PauseScene};
+pub use self::result::{BattleReport, Outcome, ResultScene};
+pub use self::title::{NewGame, TitleScene};
 
 // Shortcuts for our scene type.
 pub type FSceneSwitch = scene::SceneSwitch<World, input::InputEvent>;
@@ -0,0 +1,97 @@
+//! Lists the puzzle files in the packs folder of the user data directory.
+//! Picking one plays a random puzzle from it.
+
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input::{self, events::InputEffect, Button};
+use scenes::menu::Menu;
+use scenes::title::NewGame;
+use scenes::FSceneSwitch;
+use std::fs;
+use std::path::{Path, PathBuf};
+use world::World;
+
+const PACKS_DIR_NAME: &str = "packs";
+/// As many packs as fit on the screen above the way back.
+const MAX_PACKS: usize = 7;
+
+pub struct PacksScene {
+    packs: Vec<PathBuf>,
+    /// Indexes into `packs`, with `None` as the way back to the title.
+    menu: Menu<Option<usize>>,
+    choice: Option<Option<usize>>,
+}
+
+impl PacksScene {
+    pub fn new(user_data_dir: &Path) -> Self {
+        let packs = Self::find(&user_data_dir.join(PACKS_DIR_NAME));
+        let names: Vec<String> = packs
+            .iter()
+            .map(|path| {
+                path.file_stem()
+                    .map_or(String::new(), |name| name.to_string_lossy().into_owned())
+            })
+            .collect();
+        let mut items: Vec<(&str, Option<usize>)> = names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.as_str(), Some(i)))
+            .collect();
+        items.push(("Back", None));
+        PacksScene {
+            menu: Menu::new("Puzzle Packs", items),
+            packs,
+            choice: None,
+        }
+    }
+
+    fn find(dir: &Path) -> Vec<PathBuf> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                info!("No puzzle packs in {:?}: {}", dir, e);
+                return vec![];
+            }
+        };
+        let mut packs: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        packs.sort();
+        packs.truncate(MAX_PACKS);
+        packs
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for PacksScene {
+    fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        match self.choice.take() {
+            Some(Some(index)) => {
+                let path = self.packs[index].clone();
+                gameworld.new_game = Some(NewGame::Import(path, None));
+                scene::SceneSwitch::Pop
+            }
+            Some(None) => scene::SceneSwitch::Pop,
+            None => scene::SceneSwitch::None,
+        }
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Puzzle Packs"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        match ev {
+            InputEffect::Button(Button::Exit, _) if started => self.choice = Some(None),
+            _ => {
+                if let Some(choice) = self.menu.input(ev, started) {
+                    self.choice = Some(choice);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Pushed over the board while the game is paused. Nothing underneath is
+//! updated or drawn until it is popped, so the clock stops and the board
+//! stays hidden.
+
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input::{self, events::InputEffect, Button};
+use scenes::menu::Menu;
+use scenes::settings::SettingsScene;
+use scenes::FSceneSwitch;
+use world::World;
+
+/// What the player picked, for the scene underneath to act on once the
+/// pause menu is popped.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PauseChoice {
+    Resume,
+    Restart,
+    Settings,
+    Quit,
+}
+
+pub struct PauseScene {
+    menu: Menu<PauseChoice>,
+    choice: Option<PauseChoice>,
+}
+
+impl PauseScene {
+    pub fn new() -> Self {
+        PauseScene {
+            menu: Menu::new(
+                "Paused",
+                vec![
+                    ("Resume", PauseChoice::Resume),
+                    ("Restart", PauseChoice::Restart),
+                    ("Settings", PauseChoice::Settings),
+                    ("Quit to Title", PauseChoice::Quit),
+                ],
+            ),
+            choice: None,
+        }
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for PauseScene {
+    fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        match self.choice.take() {
+            Some(PauseChoice::Settings) => {
+                scene::SceneSwitch::Push(Box::new(SettingsScene::new(&gameworld.settings)))
+            }
+            Some(choice) => {
+                gameworld.pause_choice = Some(choice);
+                scene::SceneSwitch::Pop
+            }
+            None => scene::SceneSwitch::None,
+        }
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Pause"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        match ev {
+            InputEffect::Button(Button::Exit, _) if started => {
+                self.choice = Some(PauseChoice::Resume)
+            }
+            _ => {
+                if let Some(choice) = self.menu.input(ev, started) {
+                    self.choice = Some(choice);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+//! Replaces the board once a battle is over, with how it went and where to
+//! go next.
+
+use common::util::format_clock;
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input::{self, events::InputEffect, Button};
+use scenes::menu::Menu;
+use scenes::title::NewGame;
+use scenes::{FSceneSwitch, Gameboard};
+use world::World;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved,
+    OpponentDefeated,
+    Defeated,
+    OutOfMistakes,
+    OutOfTime,
+}
+
+impl Outcome {
+    pub fn title(self) -> &'static str {
+        match self {
+            Outcome::Solved | Outcome::OpponentDefeated => "Victory!",
+            Outcome::Defeated => "Defeated",
+            Outcome::OutOfMistakes => "Out of Mistakes",
+            Outcome::OutOfTime => "Out of Time",
+        }
+    }
+
+    pub fn is_win(self) -> bool {
+        match self {
+            Outcome::Solved | Outcome::OpponentDefeated => true,
+            _ => false,
+        }
+    }
+}
+
+/// Everything the result scene shows about a finished battle.
+#[derive(Debug, Clone)]
+pub struct BattleReport {
+    pub outcome: Outcome,
+    pub elapsed: f64,
+    /// The board as it ended, which is dealt again from the start on a retry.
+    pub gameboard: Gameboard,
+    pub rewards: Vec<String>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ResultChoice {
+    NextBattle,
+    Retry,
+    Menu,
+}
+
+pub struct ResultScene {
+    report: BattleReport,
+    menu: Menu<ResultChoice>,
+    choice: Option<ResultChoice>,
+}
+
+impl ResultScene {
+    pub fn new(report: BattleReport) -> Self {
+        let gameboard = &report.gameboard;
+        let mut lines = vec![
+            format!("Time: {}", format_clock(report.elapsed as u64)),
+            format!("Moves: {}", gameboard.moves),
+            format!("Mistakes: {}", gameboard.mistakes),
+            format!("Hints Used: {}", gameboard.hints_used),
+        ];
+        lines.extend(report.rewards.iter().cloned());
+        let menu = Menu::new(
+            report.outcome.title(),
+            vec![
+                ("Next Battle", ResultChoice::NextBattle),
+                ("Retry", ResultChoice::Retry),
+                ("Menu", ResultChoice::Menu),
+            ],
+        ).with_lines(lines);
+        ResultScene {
+            report,
+            menu,
+            choice: None,
+        }
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for ResultScene {
+    fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        let new_game = match self.choice.take() {
+            Some(ResultChoice::NextBattle) => NewGame::Deal(self.report.gameboard.rating, None),
+            Some(ResultChoice::Retry) => NewGame::Retry(self.report.gameboard.clone()),
+            Some(ResultChoice::Menu) => return scene::SceneSwitch::Pop,
+            None => return scene::SceneSwitch::None,
+        };
+        gameworld.new_game = Some(new_game);
+        scene::SceneSwitch::Pop
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Result"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        match ev {
+            InputEffect::Button(Button::Exit, _) if started => {
+                self.choice = Some(ResultChoice::Menu)
+            }
+            _ => {
+                if let Some(choice) = self.menu.input(ev, started) {
+                    self.choice = Some(choice);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Lets the player change their `Settings`. Each change is written out
+//! straight away.
+
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input::{self, events::InputEffect, Button};
+use scenes::menu::Menu;
+use scenes::FSceneSwitch;
+use settings::Settings;
+use world::World;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SettingsChoice {
+    Sound,
+    ShowConflicts,
+    Back,
+}
+
+pub struct SettingsScene {
+    menu: Menu<SettingsChoice>,
+    choice: Option<SettingsChoice>,
+}
+
+impl SettingsScene {
+    pub fn new(settings: &Settings) -> Self {
+        let mut scene = SettingsScene {
+            menu: Menu::new(
+                "Settings",
+                vec![
+                    ("", SettingsChoice::Sound),
+                    ("", SettingsChoice::ShowConflicts),
+                    ("Back", SettingsChoice::Back),
+                ],
+            ),
+            choice: None,
+        };
+        scene.relabel(settings);
+        scene
+    }
+
+    fn relabel(&mut self, settings: &Settings) {
+        let on_off = |enabled| if enabled { "On" } else { "Off" };
+        self.menu.set_label(
+            SettingsChoice::Sound,
+            &format!("Sound: {}", on_off(settings.sound)),
+        );
+        self.menu.set_label(
+            SettingsChoice::ShowConflicts,
+            &format!("Show Conflicts: {}", on_off(settings.show_conflicts)),
+        );
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for SettingsScene {
+    fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        let choice = match self.choice.take() {
+            Some(choice) => choice,
+            None => return scene::SceneSwitch::None,
+        };
+        match choice {
+            SettingsChoice::Sound => gameworld.settings.sound = !gameworld.settings.sound,
+            SettingsChoice::ShowConflicts => {
+                gameworld.settings.show_conflicts = !gameworld.settings.show_conflicts
+            }
+            SettingsChoice::Back => return scene::SceneSwitch::Pop,
+        }
+        if let Err(e) = gameworld.settings.write(&gameworld.user_data_dir) {
+            warn!("Could not save settings: {}", e);
+        }
+        self.relabel(&gameworld.settings);
+        scene::SceneSwitch::None
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Settings"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        match ev {
+            InputEffect::Button(Button::Exit, _) if started => {
+                self.choice = Some(SettingsChoice::Back)
+            }
+            _ => {
+                if let Some(choice) = self.menu.input(ev, started) {
+                    self.choice = Some(choice);
+                }
+            }
+        }
+    }
+}
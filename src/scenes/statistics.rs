@@ -0,0 +1,62 @@
+//! Shows the player's `Statistics`.
+
+use common::util::format_clock;
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input::{self, events::InputEffect, Button};
+use scenes::menu::Menu;
+use scenes::FSceneSwitch;
+use statistics::Statistics;
+use world::World;
+
+pub struct StatisticsScene {
+    /// Nothing to pick but the way back to the title.
+    menu: Menu<()>,
+    done: bool,
+}
+
+impl StatisticsScene {
+    pub fn new(statistics: &Statistics) -> Self {
+        let mut lines = vec![
+            format!("Won: {}", statistics.won),
+            format!("Lost: {}", statistics.lost),
+        ];
+        for (rating, &seconds) in &statistics.best_times {
+            let clock = format_clock(seconds as u64);
+            lines.push(format!("Best {}: {}", rating.name(), clock));
+        }
+        StatisticsScene {
+            menu: Menu::new("Statistics", vec![("Back", ())]).with_lines(lines),
+            done: false,
+        }
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for StatisticsScene {
+    fn update(&mut self, _gameworld: &mut World) -> FSceneSwitch {
+        if self.done {
+            scene::SceneSwitch::Pop
+        } else {
+            scene::SceneSwitch::None
+        }
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Statistics"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        match ev {
+            InputEffect::Button(Button::Exit, _) if started => self.done = true,
+            _ => {
+                if self.menu.input(ev, started).is_some() {
+                    self.done = true;
+                }
+            }
+        }
+    }
+}
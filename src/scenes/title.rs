@@ -0,0 +1,195 @@
+//! The first scene on the stack. Games are pushed over it, and popping a
+//! game brings the player back here.
+
+use failure;
+use ggez::{Context, GameResult};
+use ggez_goodies::scene;
+use input;
+use options::Options;
+use scenes::difficulty::DifficultyScene;
+use scenes::menu::Menu;
+use scenes::packs::PacksScene;
+use scenes::settings::SettingsScene;
+use scenes::statistics::StatisticsScene;
+use scenes::*;
+use std::path::{Path, PathBuf};
+use world::World;
+
+/// A way to start a game. Picked by the menus that lead from the title, and
+/// left in the `World` for the title to start once they are popped.
+#[derive(Debug, Clone)]
+pub enum NewGame {
+    /// Deal a board of about this rating, from the given seed or a fresh one.
+    Deal(Rating, Option<u32>),
+    Continue,
+    /// Play a puzzle from a file, or a random one from it if no index is given.
+    Import(PathBuf, Option<usize>),
+    /// Play this board again from the start.
+    Retry(Gameboard),
+}
+
+impl NewGame {
+    /// The game the command line asks to go straight into, if any.
+    pub fn from_options(options: &Options) -> Option<Self> {
+        if options.continue_game {
+            Some(NewGame::Continue)
+        } else if let Some(ref path) = options.import {
+            Some(NewGame::Import(path.clone(), Some(options.puzzle_index)))
+        } else if options.seed.is_some() {
+            Some(NewGame::Deal(options.rating, options.seed))
+        } else {
+            None
+        }
+    }
+
+    /// Builds the game, dealing a fresh board instead if there is no save to
+    /// continue or the puzzle can't be imported.
+    pub fn start(&self, options: &Options, ctx: &mut Context, world: &mut World) -> GameboardScene {
+        let (order, variant, rules) = (options.order, options.variant, &options.rules);
+        let scene = match *self {
+            NewGame::Continue => match GameboardScene::resume(ctx, world) {
+                Some(scene) => return scene,
+                None => GameboardScene::new(order, variant, rules, options.rating, ctx, world),
+            },
+            NewGame::Import(ref path, index) => {
+                let gameboard = Self::pick_index(path, index)
+                    .and_then(|index| import::load(path, index).map_err(Into::into));
+                match gameboard {
+                    Ok(gameboard) => GameboardScene::with_board(gameboard, ctx, world),
+                    Err(e) => {
+                        error!("Could not import a puzzle from {:?}: {}", path, e);
+                        GameboardScene::new(order, variant, rules, options.rating, ctx, world)
+                    }
+                }
+            }
+            NewGame::Retry(ref gameboard) => {
+                let mut gameboard = gameboard.clone();
+                gameboard.restart();
+                GameboardScene::with_board(gameboard, ctx, world)
+            }
+            NewGame::Deal(rating, Some(seed)) => {
+                GameboardScene::with_seed(seed, order, variant, rules, rating, ctx, world)
+            }
+            NewGame::Deal(rating, None) => {
+                GameboardScene::new(order, variant, rules, rating, ctx, world)
+            }
+        };
+        scene
+            .with_mistake_limit(options.mistake_limit)
+            .with_time_limit(options.time_limit)
+    }
+
+    fn pick_index(path: &Path, index: Option<usize>) -> Result<usize, failure::Error> {
+        match index {
+            Some(index) => Ok(index),
+            None => {
+                let count = import::parse_file(path)?.len().max(1);
+                Ok(fresh_seed() as usize % count)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum TitleChoice {
+    NewGame,
+    Continue,
+    PuzzlePacks,
+    Statistics,
+    Settings,
+    Quit,
+}
+
+pub struct TitleScene {
+    /// How new boards are dealt.
+    options: Options,
+    menu: Menu<TitleChoice>,
+    choice: Option<TitleChoice>,
+    /// Games need a `Context` to build, so they are started on the next draw
+    /// and pushed on the update after that.
+    starting: Option<NewGame>,
+    started: Option<GameboardScene>,
+    quitting: bool,
+}
+
+impl TitleScene {
+    pub fn new(options: Options) -> Self {
+        let starting = NewGame::from_options(&options);
+        TitleScene {
+            options,
+            menu: Menu::new(
+                "Sudoku RPG",
+                vec![
+                    ("New Game", TitleChoice::NewGame),
+                    ("Continue", TitleChoice::Continue),
+                    ("Puzzle Packs", TitleChoice::PuzzlePacks),
+                    ("Statistics", TitleChoice::Statistics),
+                    ("Settings", TitleChoice::Settings),
+                    ("Quit", TitleChoice::Quit),
+                ],
+            ),
+            choice: None,
+            starting,
+            started: None,
+            quitting: false,
+        }
+    }
+}
+
+impl scene::Scene<World, input::InputEvent> for TitleScene {
+    fn update(&mut self, gameworld: &mut World) -> FSceneSwitch {
+        if let Some(game) = self.started.take() {
+            return scene::SceneSwitch::Push(Box::new(game));
+        }
+        if let Some(new_game) = gameworld.new_game.take() {
+            self.starting = Some(new_game);
+        }
+        match self.choice.take() {
+            Some(TitleChoice::NewGame) => {
+                scene::SceneSwitch::Push(Box::new(DifficultyScene::new(self.options.rating)))
+            }
+            Some(TitleChoice::Continue) => {
+                if SaveGame::exists(&gameworld.user_data_dir) {
+                    self.starting = Some(NewGame::Continue);
+                } else {
+                    info!("There is no saved game to continue");
+                }
+                scene::SceneSwitch::None
+            }
+            Some(TitleChoice::PuzzlePacks) => {
+                scene::SceneSwitch::Push(Box::new(PacksScene::new(&gameworld.user_data_dir)))
+            }
+            Some(TitleChoice::Statistics) => {
+                scene::SceneSwitch::Push(Box::new(StatisticsScene::new(&gameworld.statistics)))
+            }
+            Some(TitleChoice::Settings) => {
+                scene::SceneSwitch::Push(Box::new(SettingsScene::new(&gameworld.settings)))
+            }
+            Some(TitleChoice::Quit) => {
+                self.quitting = true;
+                scene::SceneSwitch::None
+            }
+            None => scene::SceneSwitch::None,
+        }
+    }
+
+    fn draw(&mut self, gameworld: &mut World, ctx: &mut Context) -> GameResult<()> {
+        if self.quitting {
+            return ctx.quit();
+        }
+        if let Some(new_game) = self.starting.take() {
+            self.started = Some(new_game.start(&self.options, ctx, gameworld));
+        }
+        self.menu.draw(ctx, gameworld)
+    }
+
+    fn name(&self) -> &str {
+        "Title"
+    }
+
+    fn input(&mut self, _gameworld: &mut World, ev: input::InputEvent, started: bool) {
+        if let Some(choice) = self.menu.input(ev, started) {
+            self.choice = Some(choice);
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//! Preferences the player can change from the settings menu. They are kept
+//! in the user data directory so they carry over between games.
+
+use failure;
+use scenes::{load_ron, write_ron};
+use std::path::Path;
+
+const SETTINGS_FILE_NAME: &str = "settings.ron";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Settings {
+    /// Play sound effects.
+    #[serde(default = "enabled")]
+    pub sound: bool,
+    /// Highlight cells that break the rules.
+    #[serde(default = "enabled")]
+    pub show_conflicts: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            sound: true,
+            show_conflicts: true,
+        }
+    }
+}
+
+impl Settings {
+    /// Falls back to the defaults if nothing has been saved yet, or if the
+    /// saved settings can't be read.
+    pub fn load(dir: &Path) -> Self {
+        load_ron(dir, SETTINGS_FILE_NAME)
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<(), failure::Error> {
+        write_ron(dir, SETTINGS_FILE_NAME, self)
+    }
+}
@@ -0,0 +1,48 @@
+//! A record of every finished battle, kept in the user data directory and
+//! shown from the title screen.
+
+use failure;
+use scenes::{load_ron, write_ron, Rating};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const STATISTICS_FILE_NAME: &str = "statistics.ron";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Statistics {
+    #[serde(default)]
+    pub won: usize,
+    #[serde(default)]
+    pub lost: usize,
+    /// The fewest seconds a board of each rating has been solved in.
+    #[serde(default)]
+    pub best_times: BTreeMap<Rating, f64>,
+}
+
+impl Statistics {
+    /// Starts over from nothing if no statistics have been saved yet, or if
+    /// they can't be read.
+    pub fn load(dir: &Path) -> Self {
+        load_ron(dir, STATISTICS_FILE_NAME)
+    }
+
+    pub fn write(&self, dir: &Path) -> Result<(), failure::Error> {
+        write_ron(dir, STATISTICS_FILE_NAME, self)
+    }
+
+    /// Returns whether this was the fastest win yet at `rating`.
+    pub fn record(&mut self, rating: Rating, won: bool, elapsed: f64) -> bool {
+        if !won {
+            self.lost += 1;
+            return false;
+        }
+        self.won += 1;
+        match self.best_times.get(&rating) {
+            Some(&best) if best <= elapsed => false,
+            _ => {
+                self.best_times.insert(rating, elapsed);
+                true
+            }
+        }
+    }
+}
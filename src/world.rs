@@ -5,7 +5,10 @@
 
 use ggez;
 use input;
+use scenes;
+use settings::Settings;
 use specs;
+use statistics::Statistics;
 use std::path;
 use warmy;
 
@@ -13,6 +16,14 @@ pub struct World {
     pub assets: warmy::Store<ggez::Context>,
     pub input: input::InputState,
     pub specs_world: specs::World,
+    /// Where saves and other per-user files are written.
+    pub user_data_dir: path::PathBuf,
+    pub settings: Settings,
+    pub statistics: Statistics,
+    /// Left by the menus that lead from the title, for it to start.
+    pub new_game: Option<scenes::NewGame>,
+    /// Left by the pause menu for the scene it was paused over.
+    pub pause_choice: Option<scenes::PauseChoice>,
 }
 
 impl World {
@@ -35,11 +46,19 @@ impl World {
             .expect("Could not create asset store?  Does the directory exist?");
 
         let w = specs::World::new();
+        let user_data_dir = ctx.filesystem.get_user_data_dir().to_owned();
+        let settings = Settings::load(&user_data_dir);
+        let statistics = Statistics::load(&user_data_dir);
 
         let mut the_world = Self {
             assets: store,
             input: input::InputState::new(),
             specs_world: w,
+            user_data_dir,
+            settings,
+            statistics,
+            new_game: None,
+            pause_choice: None,
         };
 
         the_world.register_components();